@@ -0,0 +1,291 @@
+//! Puzzle answers: numbers that widen instead of overflowing, or text.
+
+use num_bigint::BigInt;
+use serde::{Deserialize, Deserializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Mul, MulAssign};
+use std::str::FromStr;
+
+/// An integer: an `i128` while it fits, an arbitrary-precision integer once it does not.
+///
+/// Arithmetic never wraps; an `i128` sum or product that would overflow is redone on big integers.
+/// Values are kept in the narrowest form, so equal numbers always compare and hash equal.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Number {
+    Int(i128),
+    Big(BigInt),
+}
+
+impl Number {
+    pub const ZERO: Number = Number::Int(0);
+    pub const ONE: Number = Number::Int(1);
+
+    pub fn is_zero(&self) -> bool {
+        *self == Number::ZERO
+    }
+
+    /// The value as an `i64`, if it fits.
+    pub fn to_i64(&self) -> Option<i64> {
+        match self {
+            Number::Int(n) => i64::try_from(*n).ok(),
+            Number::Big(_) => None,
+        }
+    }
+
+    fn to_big(&self) -> BigInt {
+        match self {
+            Number::Int(n) => BigInt::from(*n),
+            Number::Big(n) => n.clone(),
+        }
+    }
+
+    fn widen(
+        self,
+        rhs: Number,
+        checked: fn(i128, i128) -> Option<i128>,
+        big: fn(BigInt, BigInt) -> BigInt,
+    ) -> Number {
+        if let (Number::Int(a), Number::Int(b)) = (&self, &rhs) {
+            if let Some(n) = checked(*a, *b) {
+                return Number::Int(n);
+            }
+        }
+        Number::from(big(self.to_big(), rhs.to_big()))
+    }
+}
+
+impl From<BigInt> for Number {
+    fn from(n: BigInt) -> Number {
+        match i128::try_from(&n) {
+            Ok(n) => Number::Int(n),
+            Err(_) => Number::Big(n),
+        }
+    }
+}
+
+impl From<u128> for Number {
+    fn from(n: u128) -> Number {
+        match i128::try_from(n) {
+            Ok(n) => Number::Int(n),
+            Err(_) => Number::Big(BigInt::from(n)),
+        }
+    }
+}
+
+macro_rules! from_small {
+    ($($t:ty),+) => {
+        $(impl From<$t> for Number {
+            fn from(n: $t) -> Number {
+                Number::Int(n as i128)
+            }
+        })+
+    };
+}
+
+from_small!(i32, u32, i64, u64, usize, i128);
+
+impl Add for Number {
+    type Output = Number;
+
+    fn add(self, rhs: Number) -> Number {
+        self.widen(rhs, i128::checked_add, |a, b| a + b)
+    }
+}
+
+impl Mul for Number {
+    type Output = Number;
+
+    fn mul(self, rhs: Number) -> Number {
+        self.widen(rhs, i128::checked_mul, |a, b| a * b)
+    }
+}
+
+impl AddAssign for Number {
+    fn add_assign(&mut self, rhs: Number) {
+        *self = std::mem::replace(self, Number::ZERO) + rhs;
+    }
+}
+
+impl MulAssign for Number {
+    fn mul_assign(&mut self, rhs: Number) {
+        *self = std::mem::replace(self, Number::ZERO) * rhs;
+    }
+}
+
+impl Sum for Number {
+    fn sum<I: Iterator<Item = Number>>(iter: I) -> Number {
+        iter.fold(Number::ZERO, Add::add)
+    }
+}
+
+impl Product for Number {
+    fn product<I: Iterator<Item = Number>>(iter: I) -> Number {
+        iter.fold(Number::ONE, Mul::mul)
+    }
+}
+
+impl Ord for Number {
+    fn cmp(&self, other: &Number) -> Ordering {
+        match (self, other) {
+            (Number::Int(a), Number::Int(b)) => a.cmp(b),
+            _ => self.to_big().cmp(&other.to_big()),
+        }
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Number) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Number::Int(n) => n.fmt(f),
+            Number::Big(n) => n.fmt(f),
+        }
+    }
+}
+
+/// What a part returns: a number of any size, or text for puzzles whose answer is a word or list.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Number(Number),
+    Text(String),
+}
+
+impl Answer {
+    /// The items joined with commas, for puzzles that want a list as the answer.
+    pub fn list<T: fmt::Display>(items: impl IntoIterator<Item = T>) -> Answer {
+        let items: Vec<String> = items.into_iter().map(|item| item.to_string()).collect();
+        Answer::Text(items.join(","))
+    }
+
+    /// The numeric value, if this is a number.
+    pub fn as_number(&self) -> Option<&Number> {
+        match self {
+            Answer::Number(n) => Some(n),
+            Answer::Text(_) => None,
+        }
+    }
+}
+
+impl From<Number> for Answer {
+    fn from(n: Number) -> Answer {
+        Answer::Number(n)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Answer {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Answer {
+        Answer::Text(text.to_string())
+    }
+}
+
+macro_rules! answer_from_number {
+    ($($t:ty),+) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Answer {
+                Answer::Number(Number::from(n))
+            }
+        })+
+    };
+}
+
+answer_from_number!(i32, u32, i64, u64, usize, i128, u128, BigInt);
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => n.fmt(f),
+            Answer::Text(text) => f.pad(text),
+        }
+    }
+}
+
+/// Text that is an integer written the canonical way (no sign `+`, no leading zeros) is read as a
+/// number; anything else, such as `007` or `a,b`, stays text. Never fails.
+impl FromStr for Answer {
+    type Err = std::convert::Infallible;
+
+    fn from_str(text: &str) -> Result<Answer, Self::Err> {
+        match text.parse::<BigInt>() {
+            Ok(n) if n.to_string() == text => Ok(Answer::from(n)),
+            _ => Ok(Answer::from(text)),
+        }
+    }
+}
+
+/// Answers are stored as TOML integers, or as strings when they are text or too big for an `i64`.
+impl<'de> Deserialize<'de> for Answer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Answer, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Stored {
+            Int(i64),
+            Text(String),
+        }
+        Ok(match Stored::deserialize(deserializer)? {
+            Stored::Int(n) => Answer::from(n),
+            Stored::Text(text) => text.parse().unwrap_or_else(|never| match never {}),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widens_on_overflow() {
+        let big = Number::from(i128::MAX) + Number::ONE;
+        assert!(matches!(big, Number::Big(_)));
+        assert_eq!(big.to_string(), "170141183460469231731687303715884105728");
+        assert_eq!(big.clone() + Number::from(-1), Number::Int(i128::MAX));
+        assert!(big > Number::from(i128::MAX) && Number::from(-1) < big);
+
+        let product: Number = (0..40).map(|_| Number::from(u32::MAX)).product();
+        assert_eq!(product.to_string().len(), 386);
+        assert_eq!(Number::from(u128::MAX).to_string(), u128::MAX.to_string());
+    }
+
+    #[test]
+    fn narrows_to_i64_when_it_fits() {
+        let sum: Number = [i64::MAX, 1].into_iter().map(Number::from).sum();
+        assert_eq!(sum, Number::Int(i64::MAX as i128 + 1));
+        assert_eq!(sum.to_i64(), None);
+        assert_eq!(Number::from(-7i64).to_i64(), Some(-7));
+    }
+
+    #[test]
+    fn answers_compare_by_value() {
+        assert_eq!(Answer::from(42), Answer::from(42u64));
+        assert_eq!(Answer::from(42).to_string(), "42");
+        assert_ne!(Answer::from(42), Answer::from("42"));
+        assert_eq!(Answer::list([3, 1, 4]), Answer::from("3,1,4"));
+        assert_eq!(Answer::from("fgh,xyz").to_string(), "fgh,xyz");
+        let big = Answer::from(Number::from(u128::MAX) * Number::from(2));
+        assert_eq!(big.to_string(), "680564733841876926926749214863536422910");
+        assert_eq!(format!("{:<4}|{:<4}|", Answer::from(7), Answer::from("ab")), "7   |ab  |");
+    }
+
+    #[test]
+    fn parses_canonical_integers_as_numbers() {
+        let parse = |text: &str| text.parse::<Answer>().unwrap();
+        assert_eq!(parse("-12"), Answer::from(-12));
+        assert_eq!(parse("680564733841876926926749214863536422910").to_string(), "680564733841876926926749214863536422910");
+        assert!(parse("680564733841876926926749214863536422910").as_number().is_some());
+        assert_eq!(parse("007"), Answer::from("007"));
+        assert_eq!(parse("+7"), Answer::from("+7"));
+        assert_eq!(parse("1,2"), Answer::from("1,2"));
+    }
+}
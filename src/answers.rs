@@ -0,0 +1,236 @@
+use crate::answer::Answer;
+use crate::runner::Part;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Default location of the answer registry, relative to the repository root.
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Expected {
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+    /// Parameters this input needs instead of the day's defaults, e.g. `{ connections = 10 }`.
+    #[serde(default)]
+    pub params: toml::Table,
+}
+
+impl Expected {
+    pub fn get(&self, part: Part) -> Option<&Answer> {
+        match part {
+            Part::One => self.part1.as_ref(),
+            Part::Two => self.part2.as_ref(),
+        }
+    }
+
+    fn slot(&mut self, part: Part) -> &mut Option<Answer> {
+        match part {
+            Part::One => &mut self.part1,
+            Part::Two => &mut self.part2,
+        }
+    }
+}
+
+/// Verified answers keyed by day, then by input file name (e.g. `example.txt`).
+///
+/// Stored as `answers.toml`:
+///
+/// ```toml
+/// [day01."example.txt"]
+/// part1 = 3
+/// part2 = 6
+///
+/// [day08."example.txt"]
+/// params = { connections = 10 }
+/// part1 = 40
+/// ```
+///
+/// Text answers, and numbers too big for a TOML integer, are written as strings.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Answers {
+    days: BTreeMap<u8, BTreeMap<String, Expected>>,
+}
+
+/// How an answer compares to the registry.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong { expected: Answer },
+    Unverified,
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "ok"),
+            Verdict::Wrong { expected } => write!(f, "WRONG, expected {expected}"),
+            Verdict::Unverified => write!(f, "unverified"),
+        }
+    }
+}
+
+/// What recording an answer did.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Recorded {
+    New,
+    Unchanged,
+    /// A different answer is already recorded; it is kept.
+    Conflict(Answer),
+}
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Answers> {
+        let raw: BTreeMap<String, BTreeMap<String, Expected>> = toml::from_str(text)?;
+        let mut days = BTreeMap::new();
+        for (key, files) in raw {
+            let day = key
+                .strip_prefix("day")
+                .and_then(|n| n.parse::<u8>().ok())
+                .with_context(|| format!("invalid day key {key:?}, expected e.g. \"day01\""))?;
+            days.insert(day, files);
+        }
+        Ok(Answers { days })
+    }
+
+    /// Load the registry; a missing file is an empty registry.
+    pub fn load(path: &Path) -> Result<Answers> {
+        if !path.exists() {
+            return Ok(Answers::default());
+        }
+        let text = std::fs::read_to_string(path)?;
+        Answers::parse(&text).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.to_toml())?;
+        Ok(())
+    }
+
+    pub fn get(&self, day: u8, file: &str, part: Part) -> Option<&Answer> {
+        self.days.get(&day)?.get(file)?.get(part)
+    }
+
+    /// The parameter overrides recorded for an input file; empty if there are none.
+    pub fn params(&self, day: u8, file: &str) -> toml::Table {
+        self.days.get(&day).and_then(|files| files.get(file)).map(|e| e.params.clone()).unwrap_or_default()
+    }
+
+    /// Input file names with at least one recorded answer for `day`.
+    pub fn files(&self, day: u8) -> impl Iterator<Item = &str> {
+        self.days.get(&day).into_iter().flat_map(|files| files.keys().map(String::as_str))
+    }
+
+    pub fn check(&self, day: u8, file: &str, part: Part, answer: &Answer) -> Verdict {
+        match self.get(day, file, part) {
+            None => Verdict::Unverified,
+            Some(expected) if expected == answer => Verdict::Correct,
+            Some(expected) => Verdict::Wrong { expected: expected.clone() },
+        }
+    }
+
+    /// Record a verified answer. An existing, different answer is never overwritten.
+    pub fn record(&mut self, day: u8, file: &str, part: Part, answer: &Answer) -> Recorded {
+        let slot = self
+            .days
+            .entry(day)
+            .or_default()
+            .entry(file.to_string())
+            .or_default()
+            .slot(part);
+        match slot {
+            None => {
+                *slot = Some(answer.clone());
+                Recorded::New
+            }
+            Some(existing) if existing == answer => Recorded::Unchanged,
+            Some(existing) => Recorded::Conflict(existing.clone()),
+        }
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut out = String::from(
+            "# Verified puzzle answers, keyed by day and input file.\n\
+             # Checked by `aoc test` and the unit tests; add new ones with `aoc run <day> --record`.\n",
+        );
+        for (day, files) in &self.days {
+            for (file, expected) in files {
+                let _ = writeln!(out, "\n[day{day:02}.\"{file}\"]");
+                if !expected.params.is_empty() {
+                    let params: Vec<String> = expected.params.iter().map(|(k, v)| format!("{k} = {v}")).collect();
+                    let _ = writeln!(out, "params = {{ {} }}", params.join(", "));
+                }
+                for part in Part::BOTH {
+                    if let Some(answer) = expected.get(part) {
+                        let _ = writeln!(out, "part{} = {}", part.number(), toml_value(answer));
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+/// A bare integer when it fits in TOML's `i64`, otherwise a quoted string.
+fn toml_value(answer: &Answer) -> String {
+    match answer.as_number().and_then(|n| n.to_i64()) {
+        Some(n) => n.to_string(),
+        None => toml::Value::String(answer.to_string()).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_toml() {
+        let answer = |n: i64| Answer::from(n);
+        let mut answers = Answers::default();
+        assert_eq!(answers.record(3, "example.txt", Part::Two, &answer(42)), Recorded::New);
+        assert_eq!(answers.record(3, "example.txt", Part::Two, &answer(42)), Recorded::Unchanged);
+        assert_eq!(answers.record(3, "example.txt", Part::Two, &answer(7)), Recorded::Conflict(answer(42)));
+        answers.record(12, "input.txt", Part::One, &answer(1));
+        answers.record(12, "input.txt", Part::Two, &Answer::from("a \"quoted\",list"));
+        answers.record(13, "input.txt", Part::One, &Answer::from(u128::MAX));
+
+        let parsed = Answers::parse(&answers.to_toml()).unwrap();
+        assert_eq!(parsed, answers);
+        assert_eq!(parsed.get(3, "example.txt", Part::Two), Some(&answer(42)));
+        assert_eq!(parsed.get(3, "example.txt", Part::One), None);
+        assert_eq!(parsed.get(12, "input.txt", Part::One), Some(&answer(1)));
+        assert_eq!(parsed.get(13, "input.txt", Part::One), Some(&Answer::from(u128::MAX)));
+        assert_eq!(parsed.check(3, "example.txt", Part::Two, &answer(42)), Verdict::Correct);
+        assert_eq!(parsed.check(3, "example.txt", Part::Two, &answer(7)), Verdict::Wrong { expected: answer(42) });
+        assert_eq!(parsed.check(3, "input.txt", Part::Two, &answer(7)), Verdict::Unverified);
+        assert_eq!(parsed.files(3).collect::<Vec<_>>(), ["example.txt"]);
+        assert_eq!(parsed.files(4).count(), 0);
+    }
+
+    #[test]
+    fn reads_numbers_written_as_strings() {
+        let parsed = Answers::parse("[day01.\"input.txt\"]\npart1 = \"12\"\npart2 = \"012\"\n").unwrap();
+        assert_eq!(parsed.get(1, "input.txt", Part::One), Some(&Answer::from(12)));
+        assert_eq!(parsed.get(1, "input.txt", Part::Two), Some(&Answer::from("012")));
+    }
+
+    #[test]
+    fn keeps_params_next_to_the_answers() {
+        let text = "[day08.\"example.txt\"]\nparams = { connections = 10, via = [\"a\", \"b\"] }\npart1 = 40\n";
+        let mut parsed = Answers::parse(text).unwrap();
+        assert_eq!(parsed.params(8, "example.txt")["connections"].as_integer(), Some(10));
+        assert!(parsed.params(8, "input.txt").is_empty());
+        parsed.record(8, "example.txt", Part::Two, &Answer::from(25272));
+        assert_eq!(Answers::parse(&parsed.to_toml()).unwrap(), parsed);
+        assert!(parsed.to_toml().contains("params = { connections = 10, via = [\"a\", \"b\"] }\npart1 = 40\n"));
+    }
+
+    #[test]
+    fn rejects_bad_day_keys() {
+        assert!(Answers::parse("[dayone.\"input.txt\"]\npart1 = 1\n").is_err());
+        assert!(Answers::parse("[day01.\"input.txt\"]\npart3 = 1\n").is_err());
+    }
+}
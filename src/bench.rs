@@ -0,0 +1,207 @@
+use crate::days::Day;
+use anyhow::Result;
+use serde::Serialize;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// System allocator that counts allocations. Install it in a binary with
+/// `#[global_allocator] static ALLOC: CountingAllocator = CountingAllocator;`,
+/// otherwise the allocation columns of a benchmark stay at zero.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size as u64, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+fn allocation_counters() -> (u64, u64) {
+    (ALLOCATIONS.load(Ordering::Relaxed), ALLOCATED_BYTES.load(Ordering::Relaxed))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BenchConfig {
+    /// Untimed runs before sampling starts.
+    pub warmup: u32,
+    /// Maximum number of timed runs.
+    pub iterations: u32,
+    /// Stop sampling early once this much time has been spent (at least one sample is always taken).
+    pub budget: Duration,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig { warmup: 3, iterations: 100, budget: Duration::from_secs(2) }
+    }
+}
+
+/// Timing summary of one phase. Durations are in nanoseconds, allocations are per run.
+#[derive(Clone, Debug, Serialize)]
+pub struct Stats {
+    pub samples: usize,
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub p95_ns: u64,
+    pub allocations: u64,
+    pub allocated_bytes: u64,
+}
+
+impl Stats {
+    fn from_samples(mut samples: Vec<Duration>, allocations: u64, allocated_bytes: u64) -> Stats {
+        samples.sort_unstable();
+        let n = samples.len();
+        let nanos = |d: Duration| d.as_nanos() as u64;
+        Stats {
+            samples: n,
+            min_ns: nanos(samples[0]),
+            median_ns: nanos(samples[percentile_index(n, 50)]),
+            p95_ns: nanos(samples[percentile_index(n, 95)]),
+            allocations: allocations / n as u64,
+            allocated_bytes: allocated_bytes / n as u64,
+        }
+    }
+}
+
+/// Nearest-rank index of the `pct`th percentile in `n` sorted samples.
+fn percentile_index(n: usize, pct: usize) -> usize {
+    (n * pct).div_ceil(100).clamp(1, n) - 1
+}
+
+/// Run `f` repeatedly and summarise the timings. Stops at the first error.
+pub fn measure<T>(config: &BenchConfig, mut f: impl FnMut() -> Result<T>) -> Result<Stats> {
+    for _ in 0..config.warmup {
+        f()?;
+    }
+    let mut samples = Vec::with_capacity(config.iterations as usize);
+    let (allocs_before, bytes_before) = allocation_counters();
+    let started = Instant::now();
+    while samples.len() < config.iterations.max(1) as usize {
+        let now = Instant::now();
+        let result = f();
+        samples.push(now.elapsed());
+        // keep the result alive until after timing so its drop is not measured
+        drop(result?);
+        if started.elapsed() >= config.budget {
+            break;
+        }
+    }
+    let (allocs_after, bytes_after) = allocation_counters();
+    Ok(Stats::from_samples(samples, allocs_after - allocs_before, bytes_after - bytes_before))
+}
+
+#[derive(Debug, Serialize)]
+pub struct DayBench {
+    pub day: u8,
+    pub title: &'static str,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Option<Stats>,
+}
+
+/// Benchmark parsing and both parts of a day separately; the parts reuse one parsed input and run
+/// with the default parameters overridden by `params`, as `aoc run` does for the same input.
+pub fn bench_day(day: &dyn Day, input: &str, params: &toml::Table, config: &BenchConfig) -> Result<DayBench> {
+    let params = day.params(params)?;
+    let params = params.as_ref();
+    let parse = measure(config, || day.parse(input))?;
+    let parsed = day.parse(input)?;
+    let part1 = measure(config, || day.part1(parsed.as_ref(), params))?;
+    let part2 = match day.has_part2() {
+        true => Some(measure(config, || day.part2(parsed.as_ref(), params).transpose())?),
+        false => None,
+    };
+    Ok(DayBench { day: day.day(), title: day.title(), parse, part1, part2 })
+}
+
+/// A set of benchmark results as written to `--json`.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    /// Seconds since the Unix epoch when the run finished.
+    pub timestamp: u64,
+    pub profile: &'static str,
+    pub days: Vec<DayBench>,
+}
+
+impl Report {
+    pub fn new(days: Vec<DayBench>) -> Report {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let profile = if cfg!(debug_assertions) { "debug" } else { "release" };
+        Report { timestamp, profile, days }
+    }
+
+    pub fn write_json(&self, path: &std::path::Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+}
+
+/// `min / median / p95` with human-readable units, plus allocations per run.
+pub fn format_stats(stats: &Stats) -> String {
+    let d = Duration::from_nanos;
+    format!(
+        "min {:>10.2?}  median {:>10.2?}  p95 {:>10.2?}  {:>7} allocs  {:>10} B  ({} runs)",
+        d(stats.min_ns),
+        d(stats.median_ns),
+        d(stats.p95_ns),
+        stats.allocations,
+        stats.allocated_bytes,
+        stats.samples
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        assert_eq!(percentile_index(1, 50), 0);
+        assert_eq!(percentile_index(1, 95), 0);
+        assert_eq!(percentile_index(10, 50), 4);
+        assert_eq!(percentile_index(10, 95), 9);
+        assert_eq!(percentile_index(100, 95), 94);
+    }
+
+    #[test]
+    fn stats_from_samples() {
+        let samples = (1..=20).rev().map(Duration::from_nanos).collect();
+        let stats = Stats::from_samples(samples, 40, 400);
+        assert_eq!(stats.min_ns, 1);
+        assert_eq!(stats.median_ns, 10);
+        assert_eq!(stats.p95_ns, 19);
+        assert_eq!(stats.allocations, 2);
+        assert_eq!(stats.allocated_bytes, 20);
+    }
+
+    #[test]
+    fn measure_respects_iterations() {
+        let config = BenchConfig { warmup: 2, iterations: 5, budget: Duration::from_secs(60) };
+        let mut calls = 0;
+        let stats = measure(&config, || {
+            calls += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(calls, 7);
+        assert_eq!(stats.samples, 5);
+    }
+}
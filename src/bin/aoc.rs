@@ -0,0 +1,240 @@
+use anyhow::{Context, Result};
+use aoc2025::answers::{Answers, Recorded, Verdict, ANSWERS_FILE};
+use aoc2025::bench::{self, BenchConfig, CountingAllocator, Report};
+use aoc2025::days::{self, Day};
+use aoc2025::input_path;
+use aoc2025::runner::{self, Part, PartRun};
+use aoc2025::scaffold;
+use clap::{Parser, Subcommand};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[global_allocator]
+static ALLOC: CountingAllocator = CountingAllocator;
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code 2025 runner")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve a day (or every day) on its puzzle input
+    Run {
+        /// Day number to run
+        #[arg(required_unless_present = "all")]
+        day: Option<u8>,
+        /// Run every registered day
+        #[arg(long, conflicts_with = "day")]
+        all: bool,
+        /// Only run this part (1 or 2)
+        #[arg(long)]
+        part: Option<u8>,
+        /// Read the input from this file instead of inputs/dayXX/input.txt
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// Record the answers in answers.toml as verified (existing answers are kept)
+        #[arg(long)]
+        record: bool,
+        /// Also print the day's notes on the input, e.g. which machines day 10 could not solve and why
+        #[arg(long)]
+        report: bool,
+        /// Override a puzzle parameter, e.g. --param connections=10 (repeatable; applied on top of
+        /// the params recorded for the input file in answers.toml)
+        #[arg(long = "param", value_name = "KEY=VALUE", value_parser = runner::parse_param)]
+        params: Vec<(String, toml::Value)>,
+    },
+    /// Run every day on every file in inputs/dayXX and check the answers in answers.toml
+    Test {
+        /// Only test this day
+        day: Option<u8>,
+    },
+    /// Benchmark parse, part 1 and part 2 of each day on its puzzle input
+    Bench {
+        /// Only benchmark this day
+        day: Option<u8>,
+        /// Maximum number of timed runs per phase
+        #[arg(long, default_value_t = 100)]
+        iterations: u32,
+        /// Untimed warm-up runs per phase
+        #[arg(long, default_value_t = 3)]
+        warmup: u32,
+        /// Stop sampling a phase after this many milliseconds
+        #[arg(long, default_value_t = 2000)]
+        budget_ms: u64,
+        /// Also write the results as JSON to this file
+        #[arg(long)]
+        json: Option<PathBuf>,
+    },
+    /// Write the integer linear programs a day builds (days 10 and 12) as CPLEX LP and MPS files
+    Export {
+        day: u8,
+        /// Read the input from this file instead of inputs/dayXX/input.txt
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// Directory to write the models to (created if missing)
+        #[arg(long, default_value = "models")]
+        dir: PathBuf,
+        /// Only export this model, e.g. machine-003 or region-042 (repeatable)
+        #[arg(long = "model", value_name = "NAME")]
+        models: Vec<String>,
+        /// Override a puzzle parameter, as for `run`
+        #[arg(long = "param", value_name = "KEY=VALUE", value_parser = runner::parse_param)]
+        params: Vec<(String, toml::Value)>,
+    },
+    /// Generate and register a new day from the template (run from the repository root)
+    New {
+        day: u8,
+        /// Puzzle title shown by the runner
+        #[arg(long, default_value = "TODO")]
+        title: String,
+    },
+}
+
+fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::Run { day, all, part, input, record, report, params } => {
+            let answers_path = Path::new(ANSWERS_FILE);
+            let mut answers = Answers::load(answers_path)?;
+            let file = match &input {
+                Some(path) => path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+                None => "input.txt".to_string(),
+            };
+            let parts = match part {
+                Some(n) => vec![Part::from_number(n)?],
+                None => Part::BOTH.to_vec(),
+            };
+            for day in select(day)? {
+                let path = match input.clone().or_else(|| input_path(day.day())) {
+                    Some(path) => path,
+                    None if all => {
+                        println!("Day {:02}: no input, skipped", day.day());
+                        continue;
+                    }
+                    None => anyhow::bail!("No input file found for day {:02}", day.day()),
+                };
+                runner::print_header(day);
+                let mut overrides = answers.params(day.day(), &file);
+                overrides.extend(params.iter().cloned());
+                let day_run = runner::run_file(day, &path, &parts, &overrides)?;
+                println!("  {}", runner::format_parse(&day_run));
+                for run in &day_run.parts {
+                    println!("  {}{}", runner::format_part(run), verdict(&answers, day, &file, run).0);
+                    if let (true, Some(Ok(answer))) = (record, &run.answer) {
+                        match answers.record(day.day(), &file, run.part, answer) {
+                            Recorded::New => println!("  recorded {} for {file}", run.part),
+                            Recorded::Unchanged => {}
+                            Recorded::Conflict(existing) => {
+                                println!("  not recorded: {file} already has {existing} for {}", run.part)
+                            }
+                        }
+                    }
+                }
+                if report {
+                    for note in day_run.report(day)? {
+                        println!("  {note}");
+                    }
+                }
+            }
+            if record {
+                answers.save(answers_path)?;
+            }
+        }
+        Command::Test { day } => {
+            let answers = Answers::load(Path::new(ANSWERS_FILE))?;
+            let mut failures = 0;
+            for day in select(day)? {
+                runner::print_header(day);
+                for path in runner::input_files("inputs".as_ref(), day.day())? {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    let runs = match runner::run_file(day, &path, &Part::BOTH, &answers.params(day.day(), &name)) {
+                        Ok(run) => run.parts,
+                        Err(e) => {
+                            failures += 1;
+                            println!("  {name:<14}parse error: {e:#}");
+                            continue;
+                        }
+                    };
+                    for run in runs {
+                        let (suffix, failed) = verdict(&answers, day, &name, &run);
+                        if failed {
+                            failures += 1;
+                        }
+                        println!("  {name:<14}{}{suffix}", runner::format_part(&run));
+                    }
+                }
+            }
+            if failures > 0 {
+                anyhow::bail!("{failures} part(s) failed");
+            }
+        }
+        Command::Bench { day, iterations, warmup, budget_ms, json } => {
+            let config = BenchConfig { warmup, iterations, budget: Duration::from_millis(budget_ms) };
+            let answers = Answers::load(Path::new(ANSWERS_FILE))?;
+            let mut results = vec![];
+            for day in select(day)? {
+                let Some(path) = input_path(day.day()) else {
+                    println!("Day {:02}: no input, skipped", day.day());
+                    continue;
+                };
+                let text = std::fs::read_to_string(&path)?;
+                // the params recorded for this input, so the benchmark solves what `run` checks
+                let file = path.file_name().unwrap_or_default().to_string_lossy();
+                runner::print_header(day);
+                let result = bench::bench_day(day, &text, &answers.params(day.day(), &file), &config)?;
+                println!("  Parse:  {}", bench::format_stats(&result.parse));
+                println!("  Part 1: {}", bench::format_stats(&result.part1));
+                if let Some(stats) = &result.part2 {
+                    println!("  Part 2: {}", bench::format_stats(stats));
+                }
+                results.push(result);
+            }
+            if let Some(path) = json {
+                Report::new(results).write_json(&path)?;
+                println!("Wrote {}", path.display());
+            }
+        }
+        Command::Export { day, input, dir, models, params } => {
+            let day = select(Some(day))?[0];
+            let path = match input.or_else(|| input_path(day.day())) {
+                Some(path) => path,
+                None => anyhow::bail!("No input file found for day {:02}", day.day()),
+            };
+            let file = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            let mut overrides = Answers::load(Path::new(ANSWERS_FILE))?.params(day.day(), &file);
+            overrides.extend(params);
+            let written = runner::export_models(day, &path, &overrides, &models, &dir)?;
+            println!("wrote {written} model(s) to {}", dir.display());
+        }
+        Command::New { day, title } => {
+            for path in scaffold::new_day(Path::new("."), day, &title)? {
+                println!("wrote {}", path.display());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Suffix describing how a part's result compares to answers.toml, and whether it counts as a failure.
+fn verdict(answers: &Answers, day: &dyn Day, file: &str, run: &PartRun) -> (String, bool) {
+    match &run.answer {
+        Some(Ok(answer)) => match answers.check(day.day(), file, run.part, answer) {
+            v @ Verdict::Wrong { .. } => (format!("  {v}"), true),
+            v => (format!("  {v}"), false),
+        },
+        Some(Err(_)) => (String::new(), true),
+        None => (String::new(), false),
+    }
+}
+
+/// The requested day, or every registered day if none was given.
+fn select(day: Option<u8>) -> Result<Vec<&'static dyn Day>> {
+    match day {
+        Some(n) => days::get(n)
+            .map(|d| vec![d])
+            .with_context(|| format!("day {n:02} is not implemented")),
+        None => Ok(days::REGISTRY.to_vec()),
+    }
+}
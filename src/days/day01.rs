@@ -1,87 +1,130 @@
+use crate::answer::{Answer, Number};
+use crate::days::Solution;
+use crate::{lines, parse_at, ParseError};
 use anyhow::Result;
+use serde::Deserialize;
 
-pub fn part1(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, false))
+/// One line of the document: turn the dial by a number of clicks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    Left(i64),
+    Right(i64),
 }
 
-pub fn part2(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, true))
+/// The dial: how many positions it has and where it points before the first rotation.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    pub positions: i64,
+    pub start: i64,
 }
 
+impl Default for Params {
+    fn default() -> Params {
+        Params { positions: 100, start: 50 }
+    }
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Vec<Rotation>;
+    type Params = Params;
+
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Secret Entrance";
 
-fn parse_numbers(input: &str, part2: bool) -> i64 {
-    let mut zeros = 0;
-    let mut current = 50;
-    for acc in input.lines() {
-        // starts with 50, if the string starts with "R", add the number after it modulo 99
-        // if its left with "L", subtract the number after it modulo 99
-        println!("Current: {} Acc: {} Zero: {}", current, acc, zeros);
-        current = acc.trim().chars().next().map(|c| {
-            let num: i64 = acc.trim()[1..].parse().unwrap_or(0);
-            let mod_num = num % 100;
-            let amount = (num / 100).abs();
-            match c {
-                'R' => {
-                    let next = current + mod_num;
-                    if part2 {
-                        zeros = zeros + amount;
-                        if current > 0 && next > 100 {
-                            zeros = zeros + 1;
-                        }
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        let mut rotations = vec![];
+        for line in lines(input) {
+            let Some(c) = line.chars().next() else {
+                continue;
+            };
+            let num: i64 = parse_at(input, &line[c.len_utf8()..], "a rotation distance")?;
+            rotations.push(match c {
+                'L' => Rotation::Left(num),
+                'R' => Rotation::Right(num),
+                _ => return Err(ParseError::at(input, &line[..c.len_utf8()], "`L` or `R`").into()),
+            });
+        }
+        Ok(rotations)
+    }
+
+    fn part1(&self, rotations: &Self::Input, dial: &Params) -> Result<Answer> {
+        Ok(count_zeros(rotations, dial, false)?.into())
+    }
+
+    fn part2(&self, rotations: &Self::Input, dial: &Params) -> Option<Result<Answer>> {
+        Some(count_zeros(rotations, dial, true).map(Answer::from))
+    }
+}
+
+/// How often the dial ends a rotation on 0, or with `every_click` how often it passes 0 at all.
+fn count_zeros(rotations: &[Rotation], dial: &Params, every_click: bool) -> Result<Number> {
+    let positions = dial.positions;
+    anyhow::ensure!(positions > 0, "the dial needs at least one position, got {positions}");
+    let mut zeros = Number::ZERO;
+    let mut current = dial.start.rem_euclid(positions);
+    for &rotation in rotations {
+        // starts at dial.start, if the rotation is "R", add the number after it modulo the positions
+        // if its left with "L", subtract the number after it modulo the positions
+        let num = match rotation {
+            Rotation::Left(num) | Rotation::Right(num) => num,
+        };
+        let mod_num = num % positions;
+        let amount = (num / positions).abs();
+        current = match rotation {
+            Rotation::Right(_) => {
+                let next = current + mod_num;
+                if every_click {
+                    zeros += Number::from(amount);
+                    if current > 0 && next > positions {
+                        zeros += Number::ONE;
                     }
-                    next % 100
                 }
-                'L' => {
-                    let next = current - mod_num;
-                    if part2 {
-                        zeros = zeros + amount;
-                        if current > 0 && next < 0 {
-                            zeros = zeros + 1;
-                        }
+                next % positions
+            }
+            Rotation::Left(_) => {
+                let next = current - mod_num;
+                if every_click {
+                    zeros += Number::from(amount);
+                    if current > 0 && next < 0 {
+                        zeros += Number::ONE;
                     }
-                    (next + 1000000) % 100
                 }
-                _ => current,
+                next.rem_euclid(positions)
             }
-        }).unwrap_or(current);
+        };
         if current == 0 {
-            zeros = zeros + 1;
+            zeros += Number::ONE;
         }
     }
-    zeros
+    Ok(zeros)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::repo_path;
-    use std::fs;
-
-    const EXAMPLE_ANSWER_PART1: i64 = 3;
-    const EXAMPLE_ANSWER_PART2: i64 = 6;
-    const EXAMPLE_ANSWER_PART2B: i64 = 20;
+    use crate::days::testing::parse_error;
 
-    #[test]
-    fn example_part1() {
-        let path = repo_path(&["inputs", "day01", "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part1(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART1);
+    fn part1(input: &str, params: &Params) -> Answer {
+        Day01.part1(&Day01.parse(input).unwrap(), params).unwrap()
     }
 
     #[test]
-    fn example_part2() {
-        let path = repo_path(&["inputs", "day01", "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part2(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART2);
+    fn params_override_the_defaults() {
+        // a 10-position dial starting at 5 lands on 0 twice
+        assert_eq!(part1("L5\nR10\nR3\n", &Params::default()), Answer::from(0));
+        let params: Params = toml::from_str("positions = 10\nstart = 5").unwrap();
+        assert_eq!(part1("L5\nR10\nR3\n", &params), Answer::from(2));
+
+        let err = crate::days::Day::params(&Day01, &"position = 10".parse().unwrap()).unwrap_err();
+        assert_eq!(err.to_string(), "invalid parameters for day 01");
     }
 
     #[test]
-    fn example_part2b() {
-        let path = repo_path(&["inputs", "day01", "example2.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part2(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART2B);
+    fn malformed_input_reports_its_location() {
+        let err = parse_error(&Day01, "R10\nX5\n");
+        assert_eq!((err.line, err.column, err.found.as_str()), (2, 1, "X"));
     }
 }
@@ -1,35 +1,51 @@
+use crate::answer::{Answer, Number};
+use crate::days::{NoParams, Solution};
+use crate::parse;
 use anyhow::Result;
 
-pub fn part1(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, false))
-}
+pub struct Day02;
 
-pub fn part2(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, true))
-}
+impl Solution for Day02 {
+    /// The product ID ranges, inclusive.
+    type Input = Vec<(i64, i64)>;
+    type Params = NoParams;
+
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Gift Shop";
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        // two numbers separated by a dash, ranges separated by commas
+        Ok(input
+            .split(',')
+            .map(str::trim)
+            .filter(|range| !range.is_empty())
+            .map(|range| parse::range(input, range))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part1(&self, ranges: &Self::Input, _params: &NoParams) -> Result<Answer> {
+        Ok(sum_invalid(ranges, false).into())
+    }
 
+    fn part2(&self, ranges: &Self::Input, _params: &NoParams) -> Option<Result<Answer>> {
+        Some(Ok(sum_invalid(ranges, true).into()))
+    }
+}
 
-fn parse_numbers(input: &str, part2: bool) -> i64 {
+/// Sum of the IDs made of a digit sequence repeated twice (with `any_repeat`: at least twice).
+fn sum_invalid(ranges: &[(i64, i64)], any_repeat: bool) -> Number {
     let mut invalid_numbers: Vec<i64> = vec![];
-    let text = input.split(",");
-    for range in text {
-        // two numbers separated by a dash
-        let nums: Vec<&str> = range.trim().split("-").collect();
-        if nums.len() != 2 {
-            continue;
-        }
-        let start: i64 = nums[0].parse().unwrap_or(0);
-        let end: i64 = nums[1].parse().unwrap_or(0);
+    for &(start, end) in ranges {
         // we need to find every number which is twice (part 2: n-times) repeated in this range, e.g. range 11-50 has 11,22,33,44
         for i in start..=end {
             let s = i.to_string();
             // max length could be in part2 the full length of the string of the end number
-            let end_check = if part2 { nums[1].to_string().len() } else { 2 };
+            let end_check = if any_repeat { end.to_string().len() } else { 2 };
 
             for n in 2..=end_check {
                 let n_half = s.len() / n;
                 let first_half = &s[..n_half];
-                if part2 && n_half <= 0 || s.len() % n != 0 {
+                if any_repeat && n_half == 0 || s.len() % n != 0 {
                     continue;
                 }
                 for j in 0..n {
@@ -44,32 +60,17 @@ fn parse_numbers(input: &str, part2: bool) -> i64 {
             }
         }
     }
-    println!("Invalid numbers: {:?}", invalid_numbers);
-    invalid_numbers.into_iter().sum()
+    invalid_numbers.into_iter().map(Number::from).sum()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::repo_path;
-    use std::fs;
-
-    const EXAMPLE_ANSWER_PART1: i64 = 1227775554;
-    const EXAMPLE_ANSWER_PART2: i64 = 4174379265;
-
-    #[test]
-    fn example_part1() {
-        let path = repo_path(&["inputs", "day02", "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part1(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART1);
-    }
+    use crate::days::testing::parse_error;
 
     #[test]
-    fn example_part2() {
-        let path = repo_path(&["inputs", "day02", "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part2(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART2);
+    fn malformed_input_reports_its_location() {
+        let err = parse_error(&Day02, "11-22,95-1x5");
+        assert_eq!((err.line, err.column, err.found.as_str()), (1, 10, "1x5"));
     }
 }
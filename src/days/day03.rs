@@ -1,72 +1,89 @@
+use crate::answer::{Answer, Number};
+use crate::days::Solution;
+use crate::{lines, ParseError};
 use anyhow::Result;
+use serde::Deserialize;
 
-pub fn part1(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, false))
+/// How many batteries to switch on in each bank.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    pub part1_batteries: usize,
+    pub part2_batteries: usize,
 }
 
-pub fn part2(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, true))
+impl Default for Params {
+    fn default() -> Params {
+        Params { part1_batteries: 2, part2_batteries: 12 }
+    }
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    /// The joltage digit of every battery, one bank per line.
+    type Input = Vec<Vec<u8>>;
+    type Params = Params;
+
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Lobby";
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        lines(input)
+            .map(|line| {
+                line.char_indices()
+                    .map(|(i, c)| match c.to_digit(10) {
+                        Some(d) => Ok(d as u8),
+                        None => Err(ParseError::at(input, &line[i..i + c.len_utf8()], "a battery digit").into()),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn part1(&self, banks: &Self::Input, params: &Params) -> Result<Answer> {
+        total_joltage(banks, params.part1_batteries)
+    }
+
+    fn part2(&self, banks: &Self::Input, params: &Params) -> Option<Result<Answer>> {
+        Some(total_joltage(banks, params.part2_batteries))
+    }
 }
 
-fn parse_numbers(input: &str, part2: bool) -> i64 {
-    input
-        .lines()
-        .map(|line| {
-            // find the highest digit in the line, but it may not be the last digit
-            let lenght = if part2 { 12 } else { 2 };
-            let mut start = 0;
-            let mut numbers: Vec<u32> = vec![];
-            for i in 0..lenght {
-                // search the highest digit in line from start to len-i
-                let mut max_digit = 0;
-                let check_until = line.len() - lenght + i + 1;
-                for c in line[start..check_until].chars() {
-                    if let Some(d) = c.to_digit(10) {
-                        if d > max_digit {
-                            max_digit = d;
-                        }
-                    }
-                }
-                // find the position of the first occurrence of max_digit
-                if let Some(pos) = line[start..check_until].find(&max_digit.to_string()) {
-                    start = start + pos + 1;
-                    numbers.push(max_digit);
-                }
-            }
-            // join all numbers from numbers vec to a single number
-            let mut result = 0;
-            for n in numbers {
-                result = result * 10 + n as i64;
-            }
-            println!("Line: {} Max Number: {}", line, result);
-            result
-        })
-        .into_iter()
-        .sum()
+/// Sum over all banks of the largest number made by switching on `length` batteries in order.
+fn total_joltage(banks: &[Vec<u8>], length: usize) -> Result<Answer> {
+    let mut total = Number::ZERO;
+    for (index, bank) in banks.iter().enumerate() {
+        if bank.len() < length {
+            anyhow::bail!("bank {} has {} batteries, at least {length} are needed", index + 1, bank.len());
+        }
+        let mut start = 0;
+        let mut result = Number::ZERO;
+        for i in 0..length {
+            // search the highest digit in bank from start to len-length+i, taking the first occurrence
+            let check_until = bank.len() - length + i + 1;
+            let (pos, &max_digit) = bank[start..check_until]
+                .iter()
+                .enumerate()
+                .rev()
+                .max_by_key(|&(_, d)| d)
+                .unwrap_or((0, &0));
+            start += pos + 1;
+            result = result * Number::from(10) + Number::from(max_digit as u32);
+        }
+        total += result;
+    }
+    Ok(total.into())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::repo_path;
-    use std::fs;
-
-    const EXAMPLE_ANSWER_PART1: i64 = 357;
-    const EXAMPLE_ANSWER_PART2: i64 = 3121910778619;
-
-    #[test]
-    fn example_part1() {
-        let path = repo_path(&["inputs", "day03", "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part1(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART1);
-    }
 
     #[test]
-    fn example_part2() {
-        let path = repo_path(&["inputs", "day03", "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part2(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART2);
+    fn params_override_the_defaults() {
+        let params: Params = toml::from_str("part1_batteries = 3").unwrap();
+        assert_eq!(params.part2_batteries, 12);
+        assert_eq!(Day03.part1(&Day03.parse("9119\n").unwrap(), &params).unwrap(), Answer::from(919));
     }
 }
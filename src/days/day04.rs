@@ -1,67 +1,51 @@
+use crate::answer::Answer;
+use crate::days::{NoParams, Solution};
+use crate::grid::Grid;
 use anyhow::Result;
 
-pub fn part1(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, false))
-}
+pub struct Day04;
 
-pub fn part2(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, true))
-}
+impl Solution for Day04 {
+    /// `true` where a roll of paper (`@`) sits.
+    type Input = Grid<bool>;
+    type Params = NoParams;
 
-fn parse_numbers(input: &str, part2: bool) -> i64 {
-    let lines: Vec<&str> = input.lines().collect();
-    let height = lines.len();
-    let width = lines.first().map(|l| l.len()).unwrap_or(0);
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "Printing Department";
 
-    // Use a flat Vec<bool>
-    // true = '@', false = '.' or removed.
-    let mut grid = vec![false; width * height];
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        Ok(Grid::parse(input, input, "`@` or `.`", |c| match c {
+            '@' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?)
+    }
 
-    for (y, line) in lines.iter().enumerate() {
-        for (x, b) in line.bytes().enumerate() {
-            if b == b'@' {
-                grid[y * width + x] = true;
-            }
-        }
+    fn part1(&self, grid: &Self::Input, _params: &NoParams) -> Result<Answer> {
+        Ok(remove_rolls(grid.clone(), false).into())
     }
 
+    fn part2(&self, grid: &Self::Input, _params: &NoParams) -> Option<Result<Answer>> {
+        Some(Ok(remove_rolls(grid.clone(), true).into()))
+    }
+}
+
+/// Number of rolls a forklift can reach in one pass, or with `repeat` until none are left to reach.
+fn remove_rolls(mut grid: Grid<bool>, repeat: bool) -> i64 {
     let mut total_removed = 0;
-    // Reusable buffer to store indices to remove in the current step
+    // Reusable buffer to store positions to remove in the current step
     let mut to_remove = Vec::with_capacity(128);
 
     loop {
-        for y in 0..height {
-            for x in 0..width {
-                let idx = y * width + x;
-
-                // Skip if empty or already removed
-                if !grid[idx] {
-                    continue;
-                }
-
-                let mut adjacent = 0;
-
-                // Manual neighbor check with boundary guards
-                for dy in -1..=1 {
-                    for dx in -1..=1 {
-                        if dy == 0 && dx == 0 {
-                            continue;
-                        }
-
-                        let ny = y as isize + dy;
-                        let nx = x as isize + dx;
-
-                        if ny >= 0 && ny < height as isize && nx >= 0 && nx < width as isize {
-                            if grid[ny as usize * width + nx as usize] {
-                                adjacent += 1;
-                            }
-                        }
-                    }
-                }
+        for (x, y) in grid.positions() {
+            // Skip if empty or already removed
+            if !grid[(x, y)] {
+                continue;
+            }
 
-                if adjacent < 4 {
-                    to_remove.push(idx);
-                }
+            let adjacent = grid.neighbors8(x, y).filter(|&p| grid[p]).count();
+            if adjacent < 4 {
+                to_remove.push((x, y));
             }
         }
 
@@ -72,59 +56,15 @@ fn parse_numbers(input: &str, part2: bool) -> i64 {
         total_removed += to_remove.len() as i64;
 
         // Apply changes
-        for &idx in &to_remove {
-            grid[idx] = false;
+        for &p in &to_remove {
+            grid[p] = false;
         }
 
         // Clear buffer for next iteration without deallocating memory
         to_remove.clear();
 
-        if !part2 {
+        if !repeat {
             return total_removed;
         }
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::repo_path;
-    use std::fs;
-
-    const EXAMPLE_ANSWER_PART1: i64 = 13;
-    const INPUT_ANSWER_PART1: i64 = 1491;
-    const EXAMPLE_ANSWER_PART2: i64 = 43;
-    const INPUT_ANSWER_PART2: i64 = 8722;
-
-    #[test]
-    fn example_part1() {
-        let path = repo_path(&["inputs", "day04", "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part1(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART1);
-    }
-
-    #[test]
-    fn input_part1() {
-        let path = repo_path(&["inputs", "day04", "input.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part1(&input).unwrap();
-        assert_eq!(ans, INPUT_ANSWER_PART1);
-    }
-
-    #[test]
-    fn example_part2() {
-        let path = repo_path(&["inputs", "day04", "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part2(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART2);
-    }
-
-    #[test]
-    fn input_part2() {
-        let path = repo_path(&["inputs", "day04", "input.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part2(&input).unwrap();
-        assert_eq!(ans, INPUT_ANSWER_PART2);
-    }
-}
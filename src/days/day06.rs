@@ -1,137 +1,141 @@
+use crate::answer::{Answer, Number};
+use crate::days::{NoParams, Solution};
+use crate::{parse_at, ParseError};
 use anyhow::Result;
 
-pub fn part1(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, false))
+/// One column of the worksheet: its operator and its numbers read both ways.
+pub struct Problem {
+    pub op: Op,
+    /// The numbers written across, one per row.
+    pub rows: Vec<Number>,
+    /// The numbers written down, one per character column, as cephalopods read them.
+    pub columns: Vec<Number>,
 }
 
-pub fn part2(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, true))
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Multiply,
 }
 
-fn parse_numbers(input: &str, part2: bool) -> i64 {
+impl Problem {
+    fn solve(&self, numbers: &[Number]) -> Number {
+        match self.op {
+            Op::Add => numbers.iter().cloned().sum(),
+            Op::Multiply => numbers.iter().cloned().product(),
+        }
+    }
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = Vec<Problem>;
+    type Params = NoParams;
+
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Trash Compactor";
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_problems(input)
+    }
+
+    fn part1(&self, problems: &Self::Input, _params: &NoParams) -> Result<Answer> {
+        Ok(problems.iter().map(|problem| problem.solve(&problem.rows)).sum::<Number>().into())
+    }
+
+    fn part2(&self, problems: &Self::Input, _params: &NoParams) -> Option<Result<Answer>> {
+        Some(Ok(problems.iter().map(|problem| problem.solve(&problem.columns)).sum::<Number>().into()))
+    }
+}
+
+fn parse_problems(input: &str) -> Result<Vec<Problem>> {
     // the string is a grid of numbers, last row is the operator which we need to apply to each column
     let lines: Vec<&str> = input.lines().collect();
     let height = lines.len();
-    let width = lines
-        .first()
-        .unwrap()
-        .split(" ")
-        .filter(|x5| !x5.is_empty())
-        .count();
-    let mut result: i64 = 0;
-    let mut splits = lines
-        .last()
-        .unwrap()
+    if height < 2 {
+        return Err(ParseError::at(input, "", "rows of numbers followed by a row of operators").into());
+    }
+    // columns are found by position in the operator row, so every character must be one byte wide
+    for line in &lines {
+        if let Some((i, c)) = line.char_indices().find(|(_, c)| !c.is_ascii()) {
+            return Err(ParseError::at(input, &line[i..i + c.len_utf8()], "a digit, a space or an operator").into());
+        }
+    }
+    let width = lines[0].split(" ").filter(|x5| !x5.is_empty()).count();
+    let mut splits = lines[height - 1]
         .chars()
         .enumerate()
         .filter(|(_index, char)| char != &' ')
         .map(|(index, _char)| index)
         .collect::<Vec<usize>>();
+    if splits.len() != width {
+        let expected = format!("{width} operators, one per column");
+        return Err(ParseError::at(input, lines[height - 1], expected).into());
+    }
+    let ops = lines[height - 1].split(" ").filter(|x2| !x2.is_empty());
     // add an extra split at the end of the line
-    splits.push(lines.iter().map(|line| line.len()).max().unwrap());
-    for x in 0..width {
+    splits.push(lines.iter().map(|line| line.len()).max().unwrap_or(0));
+    let mut problems = vec![];
+    for (x, op) in ops.enumerate() {
+        let op = match op {
+            "+" => Op::Add,
+            "*" => Op::Multiply,
+            _ => return Err(ParseError::at(input, op, "`+` or `*`").into()),
+        };
         // the indices for the split come from the last column where the operator is not empty string
-        let filtered_lines = lines
+        let number_lines = lines[..height - 1]
             .iter()
             .map(|line| {
                 if splits[x + 1] >= line.len() {
-                    return &line[splits[x]..];
+                    return line.get(splits[x]..).unwrap_or("");
                 }
                 &line[splits[x]..splits[x + 1]]
             })
             .collect::<Vec<&str>>();
-        let number_lines = filtered_lines
+        let rows = number_lines
             .iter()
-            .take(filtered_lines.len() - 1)
-            .collect::<Vec<&&str>>();
-        let mut column_numbers: Vec<i64> = vec![];
-        if part2 {
-            // we need to read the numbers column-wise
-            // take the rightmost number of each filtered line except the last line,
-            // then the second rightmost, etc.
-            let lines_width = number_lines.iter().map(|x4| x4.len()).max().unwrap_or(0);
-            for pos in 0..lines_width {
-                let res = number_lines
-                    .iter()
-                    .flat_map(|c| {
-                        if c.len() > pos {
-                            let d = c.chars().nth(pos).unwrap();
-                            if d == ' ' {
-                                return None;
-                            }
-                            Some(d)
-                        } else {
-                            None
-                        }
-                    })
-                    .collect::<String>();
-                if res.is_empty() {
+            .map(|c| Ok(Number::from(parse_at::<i64>(input, c.trim(), "a number")?)))
+            .collect::<Result<_, ParseError>>()?;
+        // the column-wise numbers: take the leftmost character of each line except the last line,
+        // then the second leftmost, etc.
+        let mut columns = vec![];
+        let lines_width = number_lines.iter().map(|x4| x4.len()).max().unwrap_or(0);
+        for pos in 0..lines_width {
+            let mut res = Number::ZERO;
+            let mut digits = 0;
+            for c in &number_lines {
+                let Some((i, d)) = c.char_indices().nth(pos) else {
                     continue;
+                };
+                match d.to_digit(10) {
+                    Some(digit) => {
+                        res = res * Number::from(10) + Number::from(digit);
+                        digits += 1;
+                    }
+                    None if d == ' ' => {}
+                    None => return Err(ParseError::at(input, &c[i..i + d.len_utf8()], "a digit").into()),
                 }
-                column_numbers.push(res.parse().unwrap());
             }
-        } else {
-            number_lines.into_iter().for_each(|c| {
-                let d = c.trim().parse().unwrap_or(0);
-                column_numbers.push(d as i64);
-            });
-        }
-        if let Some(op) = lines[height - 1]
-            .split(" ")
-            .filter(|x2| !x2.is_empty())
-            .nth(x)
-        {
-            let column_result = match op {
-                "+" => column_numbers.iter().sum(),
-                "*" => column_numbers.iter().product(),
-                _ => 0,
-            };
-            result += column_result;
+            if digits == 0 {
+                continue;
+            }
+            columns.push(res);
         }
+        problems.push(Problem { op, rows, columns });
     }
-    result
+    Ok(problems)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::repo_path;
-    use std::fs;
-
-    const EXAMPLE_ANSWER_PART1: i64 = 4277556;
-    const INPUT_ANSWER_PART1: i64 = 4076006202939;
-    const EXAMPLE_ANSWER_PART2: i64 = 3263827;
-    const INPUT_ANSWER_PART2: i64 = 7903168391557;
-
-    #[test]
-    fn example_part1() {
-        let path = repo_path(&["inputs", "day06", "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part1(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART1);
-    }
-
-    #[test]
-    fn input_part1() {
-        let path = repo_path(&["inputs", "day06", "input.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part1(&input).unwrap();
-        assert_eq!(ans, INPUT_ANSWER_PART1);
-    }
-
-    #[test]
-    fn example_part2() {
-        let path = repo_path(&["inputs", "day06", "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part2(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART2);
-    }
+    use crate::days::testing::parse_error;
 
     #[test]
-    fn input_part2() {
-        let path = repo_path(&["inputs", "day06", "input.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part2(&input).unwrap();
-        assert_eq!(ans, INPUT_ANSWER_PART2);
+    fn non_ascii_input_reports_its_location() {
+        let input = "123 328  51 64\n 4é5 64  387 23\n  )6 98  215 314\n*  +   *   +";
+        let err = parse_error(&Day06, input);
+        assert_eq!((err.line, err.column, err.found.as_str()), (2, 3, "é"));
     }
 }
@@ -1,92 +1,62 @@
+use crate::answer::{Answer, Number};
+use crate::days::{NoParams, Solution};
+use crate::grid::Grid;
+use crate::{lines, ParseError};
 use anyhow::Result;
-use std::collections::HashMap;
 
-pub fn part1(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, false))
-}
+pub struct Day07;
 
-pub fn part2(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, true))
-}
+impl Solution for Day07 {
+    /// The manifold: `S` where the beam enters, `^` for splitters, `.` for empty space.
+    type Input = Grid<char>;
+    type Params = NoParams;
 
-fn parse_numbers(input: &str, part2: bool) -> i64 {
-    let mut splits = 0;
-    let mut counts: HashMap<(usize, usize), i64> = HashMap::new();
-    for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            if c == 'S' {
-                counts.insert((x, y), 1);
-            } else if c == '^' && y > 0 {
-                if let Some(&count) = counts.get(&(x, y - 1)) {
-                    *counts.entry((x - 1, y)).or_insert(0) += count;
-                    *counts.entry((x + 1, y)).or_insert(0) += count;
-                    if *counts.get(&(x - 1, y)).unwrap() == count
-                        || *counts.get(&(x + 1, y)).unwrap() == count
-                    {
-                        // split happened
-                        splits += 1;
-                    }
-                }
-            } else if c == '.' && y > 0 {
-                if let Some(&count) = counts.get(&(x, y - 1)) {
-                    *counts.entry((x, y)).or_insert(0) += count;
-                }
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "Laboratories";
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        let map = Grid::parse(input, input, "`S`, `^` or `.`", |c| matches!(c, 'S' | '^' | '.').then_some(c))?;
+        // a splitter on the edge would send half of its beam out of the manifold
+        for row in lines(input) {
+            let edges = [row.get(..1), row.get(row.len().saturating_sub(1)..)];
+            if let Some(edge) = edges.into_iter().flatten().find(|&edge| edge == "^") {
+                return Err(ParseError::at(input, edge, "a splitter away from the edges").into());
             }
         }
+        Ok(map)
     }
-    let max_y = counts.keys().map(|(_, y)| *y).max().unwrap_or(0);
-
-    if !part2 {
-        splits
-    } else {
-        counts
-            .into_iter()
-            .filter(|&((_, y), _)| y == max_y)
-            .map(|(_, count)| count)
-            .sum()
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::repo_path;
-    use std::fs;
-
-    const EXAMPLE_ANSWER_PART1: i64 = 21;
-    const INPUT_ANSWER_PART1: i64 = 1535;
-    const EXAMPLE_ANSWER_PART2: i64 = 40;
-    const INPUT_ANSWER_PART2: i64 = 4404709551015;
-
-    #[test]
-    fn example_part1() {
-        let path = repo_path(&["inputs", "day07", "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part1(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART1);
+    fn part1(&self, map: &Self::Input, _params: &NoParams) -> Result<Answer> {
+        Ok(trace_beams(map).0.into())
     }
 
-    #[test]
-    fn input_part1() {
-        let path = repo_path(&["inputs", "day07", "input.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part1(&input).unwrap();
-        assert_eq!(ans, INPUT_ANSWER_PART1);
+    fn part2(&self, map: &Self::Input, _params: &NoParams) -> Option<Result<Answer>> {
+        Some(Ok(trace_beams(map).1.into()))
     }
+}
 
-    #[test]
-    fn example_part2() {
-        let path = repo_path(&["inputs", "day07", "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part2(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART2);
+/// How many times the beam is split, and how many timelines reach the last row the beams get to.
+fn trace_beams(map: &Grid<char>) -> (usize, Number) {
+    let mut splits = 0;
+    // number of timelines with a beam in each cell
+    let mut counts = Grid::new(map.width(), map.height(), Number::ZERO);
+    for ((x, y), &c) in map.iter() {
+        let above = if y > 0 { counts[(x, y - 1)].clone() } else { Number::ZERO };
+        if c == 'S' {
+            counts[(x, y)] = Number::ONE;
+        } else if c == '^' && !above.is_zero() {
+            counts[(x - 1, y)] += above.clone();
+            counts[(x + 1, y)] += above.clone();
+            if counts[(x - 1, y)] == above || counts[(x + 1, y)] == above {
+                // split happened
+                splits += 1;
+            }
+        } else if c == '.' {
+            counts[(x, y)] += above;
+        }
     }
 
-    #[test]
-    fn input_part2() {
-        let path = repo_path(&["inputs", "day07", "input.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part2(&input).unwrap();
-        assert_eq!(ans, INPUT_ANSWER_PART2);
-    }
+    // the beams that made it furthest down
+    let last = counts.rows().rev().find(|row| row.iter().any(|count| !count.is_zero()));
+    (splits, last.map_or(Number::ZERO, |row| row.iter().cloned().sum()))
 }
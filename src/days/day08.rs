@@ -1,148 +1,91 @@
+use crate::answer::{Answer, Number};
+use crate::days::Solution;
+use crate::disjoint_set::DisjointSet;
+use crate::geometry::Point3;
+use crate::{lines, parse};
 use anyhow::Result;
-use std::collections::HashMap;
+use serde::Deserialize;
 
-pub fn part1(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, false))
+/// The junction boxes, and every pair of them from closest to furthest apart.
+pub struct Junctions {
+    pub boxes: Vec<Point3>,
+    /// Index pairs into `boxes`, sorted by distance.
+    pub pairs: Vec<(usize, usize)>,
 }
 
-pub fn part2(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, true))
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    /// How many of the closest pairs part 1 connects; the example uses 10.
+    pub connections: usize,
 }
 
-fn parse_numbers(input: &str, part2: bool) -> i64 {
-    // parse the list of 3-d coordinates
-    let points: Vec<(i64, i64, i64)> = input
-        .lines()
-        .map(|line| {
-            let coords: Vec<i64> = line
-                .trim()
-                .split(',')
-                .map(|s| s.parse().unwrap_or(0))
-                .collect();
-            (coords[0], coords[1], coords[2])
-        })
-        .collect();
-    // now we search for the euclidean distance between all points, to find the closest pair
-    let mut distances: Vec<((i64, i64, i64), (i64, i64, i64), i64)> = vec![];
-    for (i, point_a) in points.iter().enumerate() {
-        for (j, point_b) in points.iter().enumerate() {
-            if i >= j {
-                continue;
-            }
-            let dist = ((point_a.0 - point_b.0).pow(2)
-                + (point_a.1 - point_b.1).pow(2)
-                + (point_a.2 - point_b.2).pow(2))
-                .isqrt();
-            distances.push((*point_a, *point_b, dist));
-        }
+impl Default for Params {
+    fn default() -> Params {
+        Params { connections: 1000 }
     }
-    // sort distances by distance
-    distances.sort_by(|a, b| a.2.cmp(&b.2));
-
-    let mut point_to_group: HashMap<(i64, i64, i64), usize> = HashMap::new();
-    let mut groups: HashMap<usize, Vec<(i64, i64, i64)>> = HashMap::new();
-    let mut next_group_id = 0;
+}
 
-    let iterations = if points.len() < 100 { 10 } else { 1000 };
-    let mut iter = 0;
+pub struct Day08;
 
-    for (min_point_a, min_point_b, _) in distances {
-        if !part2 && iter >= iterations {
-            break;
-        }
-        iter += 1;
+impl Solution for Day08 {
+    type Input = Junctions;
+    type Params = Params;
 
-        let a_group = point_to_group.get(&min_point_a).copied();
-        let b_group = point_to_group.get(&min_point_b).copied();
+    const DAY: u8 = 8;
+    const TITLE: &'static str = "Playground";
 
-        match (a_group, b_group) {
-            (None, None) => {
-                let id = next_group_id;
-                next_group_id += 1;
-                point_to_group.insert(min_point_a, id);
-                point_to_group.insert(min_point_b, id);
-                groups.insert(id, vec![min_point_a, min_point_b]);
-            }
-            (Some(id), None) => {
-                point_to_group.insert(min_point_b, id);
-                groups.get_mut(&id).unwrap().push(min_point_b);
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        // parse the list of 3-d coordinates
+        let boxes: Vec<Point3> = lines(input)
+            .map(|line| parse::array(input, line, ',', "a coordinate").map(Point3::from))
+            .collect::<Result<_, _>>()?;
+        // now we search for the euclidean distance between all points, to find the closest pair;
+        // squared distances rank the pairs exactly without any rounding ties
+        let mut distances: Vec<(usize, usize, Number)> = vec![];
+        for (i, point_a) in boxes.iter().enumerate() {
+            for (j, point_b) in boxes.iter().enumerate().skip(i + 1) {
+                distances.push((i, j, point_a.distance_squared(*point_b)));
             }
-            (None, Some(id)) => {
-                point_to_group.insert(min_point_a, id);
-                groups.get_mut(&id).unwrap().push(min_point_a);
-            }
-            (Some(id_a), Some(id_b)) if id_a != id_b => {
-                // Merge groups: move all from b to a
-                if let Some(members_b) = groups.remove(&id_b) {
-                    let group_a_vec = groups.get_mut(&id_a).unwrap();
-                    for point in members_b {
-                        point_to_group.insert(point, id_a);
-                        group_a_vec.push(point);
-                    }
-                }
-            }
-            _ => {} // Already in the same group
         }
+        // sort distances by distance
+        distances.sort_by(|a, b| a.2.cmp(&b.2));
+        let pairs = distances.into_iter().map(|(a, b, _)| (a, b)).collect();
+        Ok(Junctions { boxes, pairs })
+    }
 
-        if part2 {
-            // If we have only one group left and it contains all points, we are done
-            if groups.len() == 1 && groups.values().next().unwrap().len() == points.len() {
-                return min_point_a.0 * min_point_b.0;
-            }
+    fn part1(&self, junctions: &Self::Input, params: &Params) -> Result<Answer> {
+        // every junction box starts as its own circuit
+        let mut circuits = DisjointSet::new(junctions.boxes.len());
+        for &(a, b) in junctions.pairs.iter().take(params.connections) {
+            circuits.union(a, b);
         }
+        // multiply the sizes of the 3 largest circuits
+        Ok(circuits.component_sizes().into_iter().take(3).map(Number::from).product::<Number>().into())
     }
-    // find the 3 largest groups
-    let mut result_groups: Vec<&Vec<(i64, i64, i64)>> = groups.values().collect();
-    result_groups.sort_by(|a, b| b.len().cmp(&a.len()));
-    let largest_groups = &result_groups[..3.min(result_groups.len())];
-    let mut result: i64 = 1;
-    for group in largest_groups {
-        result *= group.len() as i64;
+
+    fn part2(&self, junctions: &Self::Input, _params: &Params) -> Option<Result<Answer>> {
+        let boxes = &junctions.boxes;
+        let mut circuits = DisjointSet::new(boxes.len());
+        for &(a, b) in &junctions.pairs {
+            // If this connection joins the last two circuits, we are done
+            if circuits.union(a, b) && circuits.components() == 1 {
+                return Some(Ok((Number::from(boxes[a].x) * Number::from(boxes[b].x)).into()));
+            }
+        }
+        // fewer than two boxes: nothing ever needed connecting
+        Some(Ok(circuits.component_sizes().into_iter().take(3).map(Number::from).product::<Number>().into()))
     }
-    result
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::repo_path;
-    use std::fs;
-
-    const EXAMPLE_ANSWER_PART1: i64 = 40;
-    const INPUT_ANSWER_PART1: i64 = 153328;
-    const EXAMPLE_ANSWER_PART2: i64 = 25272;
-    const INPUT_ANSWER_PART2: i64 = 6095621910;
-    const DAY: &str = "day08";
-
-    #[test]
-    fn example_part1() {
-        let path = repo_path(&["inputs", DAY, "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part1(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART1);
-    }
-
-    #[test]
-    fn input_part1() {
-        let path = repo_path(&["inputs", DAY, "input.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part1(&input).unwrap();
-        assert_eq!(ans, INPUT_ANSWER_PART1);
-    }
-
-    #[test]
-    fn example_part2() {
-        let path = repo_path(&["inputs", DAY, "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part2(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART2);
-    }
+    use crate::days::testing::parse_error;
 
     #[test]
-    fn input_part2() {
-        let path = repo_path(&["inputs", DAY, "input.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part2(&input).unwrap();
-        assert_eq!(ans, INPUT_ANSWER_PART2);
+    fn malformed_input_reports_its_location() {
+        let err = parse_error(&Day08, "1,2,3\n4,5\n");
+        assert_eq!((err.line, err.column, err.found.as_str()), (2, 1, "4,5"));
     }
 }
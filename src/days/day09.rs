@@ -1,138 +1,58 @@
+use crate::answer::{Answer, Number};
+use crate::days::{NoParams, Solution};
+use crate::geometry::{Point2, Rect};
+use crate::{lines, parse};
 use anyhow::Result;
 
-pub fn part1(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, false))
-}
+pub struct Day09;
 
-pub fn part2(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, true))
-}
+impl Solution for Day09 {
+    /// The red tiles, in the order they are joined into a loop.
+    type Input = Vec<Point2>;
+    type Params = NoParams;
 
-fn parse_numbers(input: &str, part2: bool) -> i64 {
-    // parse the list of 2-d coordinates
-    let red_points: Vec<(i64, i64)> = input
-        .lines()
-        .map(|line| {
-            let coords: Vec<i64> = line
-                .trim()
-                .split(',')
-                .map(|s| s.parse().unwrap_or(0))
-                .collect();
-            (coords[0], coords[1])
-        })
-        .collect();
-    // now we search for the max area of two points
-    let mut max_area = 0;
-    let num_points = red_points.len();
-    for (i, point_a) in red_points.iter().enumerate() {
-        for (j, point_b) in red_points.iter().enumerate() {
-            if i >= j {
-                continue;
-            }
+    const DAY: u8 = 9;
+    const TITLE: &'static str = "Movie Theater";
 
-            if part2 {
-                let min_x = point_a.0.min(point_b.0);
-                let max_x = point_a.0.max(point_b.0);
-                let min_y = point_a.1.min(point_b.1);
-                let max_y = point_a.1.max(point_b.1);
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        // parse the list of 2-d coordinates
+        Ok(lines(input)
+            .map(|line| parse::array(input, line, ',', "a coordinate").map(Point2::from))
+            .collect::<Result<_, _>>()?)
+    }
 
-                let mut crossing = false;
-                for k in 0..num_points {
-                    let p1 = red_points[k];
-                    let p2 = red_points[(k + 1) % num_points];
-                    if intersects_rect(p1, p2, min_x, max_x, min_y, max_y) {
-                        crossing = true;
-                        break;
-                    }
-                }
-                if crossing {
-                    continue;
-                }
-            }
+    fn part1(&self, red_points: &Self::Input, _params: &NoParams) -> Result<Answer> {
+        Ok(largest_rectangle(red_points, false).into())
+    }
 
-            let dist = ((point_a.0 - point_b.0).abs() + 1) * ((point_a.1 - point_b.1).abs() + 1);
-            if dist > max_area {
-                max_area = dist;
-            }
-        }
+    fn part2(&self, red_points: &Self::Input, _params: &NoParams) -> Option<Result<Answer>> {
+        Some(Ok(largest_rectangle(red_points, true).into()))
     }
-    max_area
 }
 
-fn intersects_rect(p1: (i64, i64), p2: (i64, i64), min_x: i64, max_x: i64, min_y: i64, max_y: i64) -> bool {
-    // Check if the segment p1-p2 strictly intersects the interior of the rectangle
-    // We look for a parameter t in (0, 1) such that p(t) is strictly inside (min_x, max_x) x (min_y, max_y)
-
-    let get_interval = |p_start: i64, p_end: i64, min_val: i64, max_val: i64| -> Option<(f64, f64)> {
-        let d = (p_end - p_start) as f64;
-        if d == 0.0 {
-            if p_start > min_val && p_start < max_val {
-                Some((-f64::INFINITY, f64::INFINITY))
-            } else {
-                None
+/// Largest area of a rectangle with red tiles in opposite corners; with `inside_loop`, only
+/// rectangles that no edge of the loop cuts through count.
+fn largest_rectangle(red_points: &[Point2], inside_loop: bool) -> Number {
+    // now we search for the max area of two points
+    let mut max_area = Number::ZERO;
+    let num_points = red_points.len();
+    for (i, &point_a) in red_points.iter().enumerate() {
+        for &point_b in &red_points[i + 1..] {
+            let rect = Rect::spanning(point_a, point_b);
+            let area = Number::from(rect.width()) * Number::from(rect.height());
+            if area <= max_area {
+                continue;
             }
-        } else {
-            let t1 = (min_val - p_start) as f64 / d;
-            let t2 = (max_val - p_start) as f64 / d;
-            Some((t1.min(t2), t1.max(t2)))
-        }
-    };
 
-    let x_interval = get_interval(p1.0, p2.0, min_x, max_x);
-    let y_interval = get_interval(p1.1, p2.1, min_y, max_y);
+            // part 2: no edge of the loop may cut through the inside of the rectangle
+            if inside_loop && (0..num_points)
+                .any(|k| rect.segment_crosses_interior(red_points[k], red_points[(k + 1) % num_points]))
+            {
+                continue;
+            }
 
-    match (x_interval, y_interval) {
-        (Some((t0_x, t1_x)), Some((t0_y, t1_y))) => {
-            let start = 0.0f64.max(t0_x).max(t0_y);
-            let end = 1.0f64.min(t1_x).min(t1_y);
-            // If the intersection interval is valid and non-empty (start < end), we have a crossing
-            start < end
+            max_area = area;
         }
-        _ => false,
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::repo_path;
-    use std::fs;
-
-    const EXAMPLE_ANSWER_PART1: i64 = 50;
-    const INPUT_ANSWER_PART1: i64 = 4741848414;
-    const EXAMPLE_ANSWER_PART2: i64 = 24;
-    const INPUT_ANSWER_PART2: i64 = 6095621910;
-    const DAY: &str = "day09";
-
-    #[test]
-    fn example_part1() {
-        let path = repo_path(&["inputs", DAY, "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part1(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART1);
-    }
-
-    #[test]
-    fn input_part1() {
-        let path = repo_path(&["inputs", DAY, "input.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part1(&input).unwrap();
-        assert_eq!(ans, INPUT_ANSWER_PART1);
-    }
-
-    #[test]
-    fn example_part2() {
-        let path = repo_path(&["inputs", DAY, "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part2(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART2);
-    }
-
-    #[test]
-    fn input_part2() {
-        let path = repo_path(&["inputs", DAY, "input.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part2(&input).unwrap();
-        assert_eq!(ans, INPUT_ANSWER_PART2);
     }
+    max_area
 }
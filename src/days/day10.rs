@@ -1,138 +1,404 @@
-use good_lp::{default_solver, variable, variables, Solution, SolverModel};
-
+use crate::answer::{Answer, Number};
+use crate::days::Solution;
+use crate::gf2::System;
+use crate::ilp::{self, Pending, Relation};
+use crate::linear;
+use crate::{lines, parse, ParseError};
 use anyhow::Result;
+use serde::Deserialize;
+use std::fmt;
+
+/// One machine from the manual.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Machine {
+    /// The indicator light diagram: `true` for the lights that must end up on.
+    pub lights: Vec<bool>,
+    /// For each button, the lights (and joltage counters) it is wired to.
+    pub buttons: Vec<Vec<usize>>,
+    pub joltages: Vec<u32>,
+}
+
+/// How part 2 finds the fewest presses.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Solver {
+    /// The in-crate search in [`crate::linear`].
+    #[default]
+    Exact,
+    /// An integer linear program for the backend picked at build time (see [`crate::ilp`]).
+    Ilp,
+}
 
-pub fn part1(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, false))
-}
-
-pub fn part2(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, true))
-}
-
-fn parse_numbers(input: &str, part2: bool) -> i64 {
-    let mut score = 0;
-
-    for line in input.lines() {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-
-        // Parse buttons (1,2)
-        let buttons_indices: Vec<Vec<usize>> = parts[1..parts.len() - 1].iter().map(|button_str| {
-            button_str.trim_matches(|c| c == '(' || c == ')')
-                .split(',')
-                .filter(|s| !s.is_empty())
-                .map(|s| s.parse().unwrap())
-                .collect()
-        }).collect();
-
-        // Determine Targets and Variable Constraints based on Part
-        let targets: Vec<f64>;
-
-        if !part2 {
-            // Part 1: Target is the pattern itself [.##.] -> [0, 1, 1, 0]
-            // Constraint: Use each button at most once (Binary)
-            let pattern = parts[0].trim_matches(|c| c == '[' || c == ']');
-            targets = pattern.chars()
-                .map(|c| if c == '#' { 1.0 } else { 0.0 })
-                .collect();
-        } else {
-            // Part 2: Target is the number list {55, 38...}
-            // Constraint: Unlimited presses
-            targets = parts.last().unwrap()
-                .trim_matches(|c| c == '{' || c == '}')
-                .split(',')
-                .filter(|s| !s.is_empty())
-                .map(|s| s.parse().unwrap())
-                .collect();
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    pub solver: Solver,
+}
+
+impl Machine {
+    /// The lights after pressing button `b` `presses[b]` times, starting with every light off, or
+    /// an error if a pressed button is wired to a light the machine does not have.
+    pub fn lights_after(&self, presses: &[u64]) -> Result<Vec<bool>> {
+        let mut lights = vec![false; self.lights.len()];
+        for (b, (wired, &n)) in self.buttons.iter().zip(presses).enumerate() {
+            if n % 2 == 1 {
+                for &light in wired {
+                    *wire(&mut lights, b, light, "light")? ^= true;
+                }
+            }
         }
+        Ok(lights)
+    }
 
-        // --- ILP SOLVER ---
-        let mut vars = variables!();
-        let mut button_vars = Vec::new();
-
-        for _ in 0..buttons_indices.len() {
-            // Unified Variable Definition
-            // Part 1: min(0), max(1), Integer -> Binary Variable (0 or 1)
-            // Part 2: min(0), Integer -> standard Integer Variable (0, 1, 2...)
-            if !part2 {
-                button_vars.push(vars.add(variable().integer().min(0).max(1)));
-            } else {
-                button_vars.push(vars.add(variable().integer().min(0)));
+    /// The joltage counters after pressing button `b` `presses[b]` times, starting from zero, or an
+    /// error if a button is wired to a counter the machine does not have.
+    pub fn joltages_after(&self, presses: &[u64]) -> Result<Vec<u64>> {
+        let mut counters = vec![0; self.joltages.len()];
+        for (b, (wired, &n)) in self.buttons.iter().zip(presses).enumerate() {
+            for &c in wired {
+                *wire(&mut counters, b, c, "counter")? += n;
             }
         }
+        Ok(counters)
+    }
+
+    /// Replay `presses`, one count per button, and check they light exactly the pattern.
+    pub fn check_lights(&self, presses: &[u64]) -> Result<()> {
+        self.check_length(presses)?;
+        let lit = self.lights_after(presses)?;
+        anyhow::ensure!(lit == self.lights, "presses {presses:?} light {} instead of {}", diagram(&lit), diagram(&self.lights));
+        Ok(())
+    }
+
+    /// Replay `presses`, one count per button, and check every counter lands on its joltage.
+    pub fn check_joltages(&self, presses: &[u64]) -> Result<()> {
+        self.check_length(presses)?;
+        let reached = self.joltages_after(presses)?;
+        let wanted: Vec<u64> = self.joltages.iter().map(|&j| u64::from(j)).collect();
+        anyhow::ensure!(reached == wanted, "presses {presses:?} reach joltages {reached:?} instead of {wanted:?}");
+        Ok(())
+    }
 
-        let objective = button_vars.iter().sum::<good_lp::Expression>();
+    fn check_length(&self, presses: &[u64]) -> Result<()> {
+        anyhow::ensure!(
+            presses.len() == self.buttons.len(),
+            "{} press counts for {} buttons",
+            presses.len(),
+            self.buttons.len()
+        );
+        Ok(())
+    }
+}
 
-        // Use Highs solver (robust for ILP)
-        let mut problem = vars.minimise(objective).using(default_solver);
+/// The light or counter `i` that button `b` is wired to.
+fn wire<'a, T>(slots: &'a mut [T], b: usize, i: usize, what: &str) -> Result<&'a mut T> {
+    let n = slots.len();
+    slots.get_mut(i).ok_or_else(|| anyhow::anyhow!("button {b} is wired to {what} {i}, but the machine has {n}"))
+}
 
-        // Constraints: Sum(Button_Impacts) == Target
-        // Note: For Part 1, this assumes "Addition" logic matches the "Pattern" logic.
-        // If Part 1 is strictly XOR (Modulo 2), this ILP model is valid ONLY IF
-        // the combination is unique and doesn't rely on 1+1=0 cancellation.
-        // Given Part 2 works with addition, Part 1 likely does too.
-        for (jolt_idx, &target_val) in targets.iter().enumerate() {
-            let mut expression = good_lp::Expression::from(0);
+/// Lights as the manual draws them, e.g. `[.##.]`.
+fn diagram(lights: &[bool]) -> String {
+    let lights: String = lights.iter().map(|&on| if on { '#' } else { '.' }).collect();
+    format!("[{lights}]")
+}
 
-            for (btn_i, affected_indices) in buttons_indices.iter().enumerate() {
-                let impact = affected_indices.iter().filter(|&&x| x == jolt_idx).count() as i32;
-                if impact > 0 {
-                    expression += button_vars[btn_i] * impact;
-                }
+/// The machine as the manual writes it, e.g. `[.##.] (3) (1,3) {3,5,4,7}`.
+impl fmt::Display for Machine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", diagram(&self.lights))?;
+        for wired in &self.buttons {
+            write!(f, " ({})", wired.iter().map(|l| l.to_string()).collect::<Vec<_>>().join(","))?;
+        }
+        write!(f, " {{{}}}", self.joltages.iter().map(|j| j.to_string()).collect::<Vec<_>>().join(","))
+    }
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<Machine>;
+    type Params = Params;
+
+    const DAY: u8 = 10;
+    const TITLE: &'static str = "Factory";
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        lines(input).map(|line| parse_machine(input, line)).collect()
+    }
+
+    fn part1(&self, machines: &Self::Input, _params: &Params) -> Result<Answer> {
+        total(light_results(machines), "no combination of buttons lights the pattern")
+    }
+
+    fn part2(&self, machines: &Self::Input, params: &Params) -> Option<Result<Answer>> {
+        Some(total(joltage_results(machines, params.solver), "no combination of presses reaches the joltages"))
+    }
+
+    /// The machines named in the parts' [`Unsolved`] errors, or that every machine was solved.
+    fn report(&self, machines: &Self::Input, outcomes: &[(u8, &Result<Answer>)]) -> Vec<String> {
+        let mut notes = vec![];
+        for &(part, outcome) in outcomes {
+            let Some(unsolved) = outcome.as_ref().err().and_then(|e| e.downcast_ref::<Unsolved>()) else {
+                continue;
+            };
+            for result in unsolved.results.iter().filter(|r| r.status != Status::Optimal) {
+                let why = match &result.status {
+                    Status::Infeasible => "infeasible",
+                    Status::Failed(reason) => reason,
+                    Status::Optimal => unreachable!(),
+                };
+                notes.push(format!("machine {} `{}`: part {part} {why}", result.index, machines[result.index - 1]));
             }
-            problem.add_constraint(expression.eq(target_val));
         }
+        if notes.is_empty() && outcomes.iter().all(|(_, outcome)| outcome.is_ok()) {
+            notes.push(format!("all {} machines solved", machines.len()));
+        }
+        notes
+    }
+
+    fn models<'a>(&self, machines: &'a Self::Input, _params: &'a Params) -> Vec<Pending<'a>> {
+        let models = machines.iter().enumerate();
+        models.map(|(i, machine)| Pending::new(format!("machine-{:03}", i + 1), move || presses_model(machine))).collect()
+    }
+}
+
+/// How solving one machine ended.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// The presses found are the fewest that work.
+    Optimal,
+    /// No combination of presses reaches the target.
+    Infeasible,
+    /// The solver gave up without an answer, e.g. an ILP backend error; the text says why.
+    Failed(String),
+}
 
-        if let Ok(solution) = problem.solve() {
-            let presses: f64 = solution.eval(button_vars.iter().sum::<good_lp::Expression>());
-            score += (presses + 1e-5).round() as i64;
+/// The outcome for one machine, numbered from 1 in the order of the manual.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MachineResult {
+    pub index: usize,
+    /// The fewest presses, when the status is [`Status::Optimal`].
+    pub presses: Option<u64>,
+    /// How often to press each button to get there, in the order the manual lists them.
+    pub buttons: Option<Vec<u64>>,
+    pub status: Status,
+}
+
+impl MachineResult {
+    /// The result of a solver that found `solved` for machine `index` (from 0), once `check` has
+    /// replayed the presses: a witness that misses the target counts as a failed solve.
+    fn new(index: usize, solved: Result<Option<Vec<u64>>>, check: impl FnOnce(&[u64]) -> Result<()>) -> MachineResult {
+        let checked = solved.and_then(|buttons| buttons.map(|b| check(&b).map(|()| b)).transpose());
+        let (buttons, status) = match checked {
+            Ok(Some(buttons)) => (Some(buttons), Status::Optimal),
+            Ok(None) => (None, Status::Infeasible),
+            Err(e) => (None, Status::Failed(format!("{e:#}"))),
+        };
+        let presses = buttons.as_ref().map(|b| b.iter().sum());
+        MachineResult { index: index + 1, presses, buttons, status }
+    }
+}
+
+/// Fewest presses to light each machine's pattern (part 1).
+pub fn light_results(machines: &[Machine]) -> Vec<MachineResult> {
+    let results = machines.iter().enumerate();
+    results.map(|(i, m)| MachineResult::new(i, fewest_toggles(m), |b| m.check_lights(b))).collect()
+}
+
+/// Fewest presses to reach each machine's joltages (part 2).
+pub fn joltage_results(machines: &[Machine], solver: Solver) -> Vec<MachineResult> {
+    let solve = |m| match solver {
+        Solver::Exact => fewest_presses(m),
+        Solver::Ilp => fewest_presses_ilp(m),
+    };
+    let results = machines.iter().enumerate();
+    results.map(|(i, m)| MachineResult::new(i, solve(m), |b| m.check_joltages(b))).collect()
+}
+
+/// The error a part returns when some machine has no answer: the message names the machines, and
+/// every machine's result is kept for [`Day10`]'s report.
+#[derive(Debug)]
+pub struct Unsolved {
+    message: String,
+    pub results: Vec<MachineResult>,
+}
+
+impl fmt::Display for Unsolved {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Unsolved {}
+
+/// The sum over every machine, or an [`Unsolved`] error naming the infeasible machines
+/// (`infeasible` says what they lack) and the first machine the solver failed on.
+fn total(results: Vec<MachineResult>, infeasible: &str) -> Result<Answer> {
+    let unsolvable: Vec<String> = results.iter().filter(|r| r.status == Status::Infeasible).map(|r| r.index.to_string()).collect();
+    let failed: Vec<(usize, &str)> = results
+        .iter()
+        .filter_map(|r| match &r.status {
+            Status::Failed(reason) => Some((r.index, reason.as_str())),
+            _ => None,
+        })
+        .collect();
+    let mut problems = vec![];
+    if !unsolvable.is_empty() {
+        problems.push(format!("{infeasible} on machine(s) {}", unsolvable.join(", ")));
+    }
+    if let Some(&(index, reason)) = failed.first() {
+        problems.push(format!("the solver failed on {} machine(s), first on machine {index}: {reason}", failed.len()));
+    }
+    if !problems.is_empty() {
+        return Err(Unsolved { message: problems.join("; "), results }.into());
+    }
+    Ok(results.iter().map(|r| Number::from(r.presses.unwrap_or(0))).sum::<Number>().into())
+}
+
+fn parse_machine(input: &str, line: &str) -> Result<Machine> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() < 2 {
+        return Err(ParseError::at(input, line, "`[lights] (buttons)... {joltages}`").into());
+    }
+
+    let pattern = parse::bracketed(input, parts[0], '[', ']')?;
+    let lights = pattern
+        .char_indices()
+        .map(|(i, c)| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(ParseError::at(input, &pattern[i..i + c.len_utf8()], "`#` or `.`")),
+        })
+        .collect::<Result<_, _>>()?;
+
+    // Parse buttons (1,2)
+    let mut buttons = vec![];
+    for button in &parts[1..parts.len() - 1] {
+        let wired: Vec<usize> = parse::list(input, parse::bracketed(input, button, '(', ')')?, ',', "a light index")?;
+        if wired.iter().any(|&light| light >= pattern.len()) {
+            let expected = format!("light indices below {}", pattern.len());
+            return Err(ParseError::at(input, button, expected).into());
         }
+        buttons.push(wired);
+    }
+
+    // one counter per light, so the buttons' indices are valid for both
+    let list = parts[parts.len() - 1];
+    let joltages: Vec<u32> = parse::list(input, parse::bracketed(input, list, '{', '}')?, ',', "a joltage")?;
+    if joltages.len() != pattern.len() {
+        return Err(ParseError::at(input, list, format!("{} joltages", pattern.len())).into());
+    }
+    Ok(Machine { lights, buttons, joltages })
+}
+
+/// How often to press each button, fewest presses in all, to light exactly the pattern, or `None`
+/// if no combination does.
+///
+/// Pressing a button twice undoes it, so each button is pressed at most once and light `i` ends up
+/// on when an odd number of the pressed buttons toggle it: one equation over GF(2) per light.
+fn fewest_toggles(machine: &Machine) -> Result<Option<Vec<u64>>> {
+    let mut system = System::new(machine.buttons.len());
+    for (light, &on) in machine.lights.iter().enumerate() {
+        let buttons = machine.buttons.iter().enumerate().filter(|(_, wired)| wired.contains(&light));
+        system.equation(buttons.map(|(b, _)| b), on);
+    }
+    let presses = system.min_weight_solution()?;
+    Ok(presses.map(|presses| presses.into_iter().map(u64::from).collect()))
+}
+
+/// How often to press each button, fewest presses in all, to make every counter reach its joltage,
+/// each press adding one to the counters the button is wired to, or `None` if no combination does.
+/// One equation per counter.
+fn fewest_presses(machine: &Machine) -> Result<Option<Vec<u64>>> {
+    let mut system = linear::System::new(machine.buttons.len());
+    for (counter, &joltage) in machine.joltages.iter().enumerate() {
+        let buttons = machine.buttons.iter().enumerate();
+        let wired = buttons.flat_map(|(b, wired)| wired.iter().filter(move |&&c| c == counter).map(move |_| b));
+        system.equation(wired, u64::from(joltage));
     }
+    Ok(system.min_sum_solution()?)
+}
 
-    score
+/// [`fewest_presses`] as an integer linear program: one non-negative integer variable per button.
+fn fewest_presses_ilp(machine: &Machine) -> Result<Option<Vec<u64>>> {
+    Ok(presses_model(machine).solve()?.map(|presses| presses.into_iter().map(|p| p.max(0) as u64).collect()))
+}
+
+fn presses_model(machine: &Machine) -> ilp::Model {
+    let mut model = ilp::Model::new();
+    let buttons: Vec<usize> = (0..machine.buttons.len()).map(|b| model.variable(format!("b{b}"), 0, None)).collect();
+    for (counter, &joltage) in machine.joltages.iter().enumerate() {
+        let wired = machine.buttons.iter().zip(&buttons);
+        let terms = wired.map(|(wired, &b)| (b, wired.iter().filter(|&&c| c == counter).count() as i64));
+        model.constraint(format!("c{counter}"), terms.filter(|&(_, n)| n > 0), Relation::Equal, i64::from(joltage));
+    }
+    model.minimise(buttons.iter().map(|&b| (b, 1)));
+    model
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::repo_path;
-    use std::fs;
+    use crate::days::testing::{assert_models_named, assert_solvers_agree, example, parse_error};
 
-    const EXAMPLE_ANSWER_PART1: i64 = 7;
-    const INPUT_ANSWER_PART1: i64 = 434;
-    const EXAMPLE_ANSWER_PART2: i64 = 33;
-    const INPUT_ANSWER_PART2: i64 = 15132;
-    const DAY: &str = "day10";
+    #[test]
+    fn malformed_input_reports_its_location() {
+        let err = parse_error(&Day10, "[.#] (0) (1,q) {1,2}\n");
+        assert_eq!((err.line, err.column, err.found.as_str()), (1, 13, "q"));
+        let err = parse_error(&Day10, "[.#] (0) (1,2) {1,2}\n");
+        assert_eq!((err.column, err.expected.as_str()), (10, "light indices below 2"));
+        let err = parse_error(&Day10, "[.#] (0,1) {1}\n");
+        assert_eq!((err.column, err.found.as_str(), err.expected.as_str()), (12, "{1}", "2 joltages"));
+    }
 
     #[test]
-    fn example_part1() {
-        let path = repo_path(&["inputs", DAY, "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part1(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART1);
+    fn solvers_agree_on_the_example() {
+        assert_solvers_agree(&Day10, 2, &["exact"], Answer::from(33));
     }
 
     #[test]
-    fn input_part1() {
-        let path = repo_path(&["inputs", DAY, "input.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part1(&input).unwrap();
-        assert_eq!(ans, INPUT_ANSWER_PART1);
+    fn names_the_machines_it_cannot_solve() {
+        // machine 2 cannot reach {1,2} pressing both counters at once; machine 3 cannot light one of two
+        let machines = Day10.parse("[.#] (0) (1) {1,2}\n[##] (0,1) {1,2}\n[#.] (0,1) {1,1}\n").unwrap();
+        let params = Params::default();
+        let part1 = Day10.part1(&machines, &params);
+        assert_eq!(part1.as_ref().unwrap_err().to_string(), "no combination of buttons lights the pattern on machine(s) 3");
+        let part2 = Day10.part2(&machines, &params).unwrap();
+        assert_eq!(part2.as_ref().unwrap_err().to_string(), "no combination of presses reaches the joltages on machine(s) 2");
+        // the report comes from the errors the parts returned
+        let report = Day10.report(&machines, &[(1, &part1), (2, &part2)]);
+        assert_eq!(report, ["machine 3 `[#.] (0,1) {1,1}`: part 1 infeasible", "machine 2 `[##] (0,1) {1,2}`: part 2 infeasible"]);
+        let machines = example(&Day10);
+        let solved = Day10.part1(&machines, &params);
+        assert_eq!(Day10.report(&machines, &[(1, &solved)]), ["all 3 machines solved"]);
     }
 
     #[test]
-    fn example_part2() {
-        let path = repo_path(&["inputs", DAY, "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part2(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART2);
+    fn witnesses_replay_to_the_target() {
+        let machines = example(&Day10);
+        let lights = light_results(&machines);
+        let joltages = joltage_results(&machines, Solver::Exact);
+        for ((machine, lit), reached) in machines.iter().zip(&lights).zip(&joltages) {
+            machine.check_lights(lit.buttons.as_ref().unwrap()).unwrap();
+            machine.check_joltages(reached.buttons.as_ref().unwrap()).unwrap();
+        }
+        assert_eq!(lights.iter().map(|r| r.presses.unwrap()).sum::<u64>(), 7);
+        assert_eq!(joltages.iter().map(|r| r.presses.unwrap()).sum::<u64>(), 33);
+
+        let mut tampered = joltages[0].buttons.clone().unwrap();
+        tampered[0] += 1;
+        assert!(machines[0].check_joltages(&tampered).is_err());
+        assert!(machines[0].check_lights(&[1]).is_err());
+
+        // a counter the machine does not have fails the replay instead of being skipped
+        let mut miswired = machines[0].clone();
+        miswired.buttons[0].push(miswired.joltages.len());
+        let err = miswired.check_joltages(joltages[0].buttons.as_ref().unwrap()).unwrap_err();
+        assert_eq!(err.to_string(), "button 0 is wired to counter 4, but the machine has 4");
     }
 
     #[test]
-    fn input_part2() {
-        let path = repo_path(&["inputs", DAY, "input.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part2(&input).unwrap();
-        assert_eq!(ans, INPUT_ANSWER_PART2);
+    fn names_a_model_per_machine() {
+        assert_models_named(&Day10, "machine", 3);
     }
 }
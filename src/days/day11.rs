@@ -1,150 +1,97 @@
+use crate::answer::Answer;
+use crate::days::Solution;
+use crate::graph::Graph;
+use crate::{lines, parse};
 use anyhow::Result;
-use std::collections::HashMap;
-
-pub fn part1(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, false))
-}
-
-pub fn part2(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input, true))
+use serde::Deserialize;
+
+/// The devices the paths run between.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    /// Where part 1 starts.
+    pub start: String,
+    /// Where part 2 starts.
+    pub server: String,
+    pub target: String,
+    /// Devices every path of part 2 must visit.
+    pub via: Vec<String>,
 }
 
-fn parse_numbers(input: &str, part2: bool) -> i64 {
-    // each line is a node with outgoing edges to other nodes
-    let mut result = 0;
-    let edges: HashMap<String, Vec<String>> = input
-        .lines()
-        .map(|line| {
-            let parts: Vec<&str> = line.split(":").collect();
-            let key = parts[0].trim().to_string();
-
-            let values = parts
-                .get(1)
-                .unwrap_or(&"")
-                .trim()
-                .split(" ")
-                .filter(|s| !s.is_empty())
-                .map(|s| s.trim().to_string())
-                .collect();
-            (key, values)
-        })
-        .collect();
-
-    if !part2 {
-        // start a bfs from node "you" to node "out" and find the number of possible paths
-        let start_node = "you".to_string();
-        let target_node = "out".to_string();
-        let mut queue: Vec<(String, Vec<String>)> =
-            vec![(start_node.clone(), vec![start_node.clone()])];
-        while let Some((current_node, path)) = queue.pop() {
-            if current_node == target_node {
-                result += 1;
-                continue;
-            }
-
-            if let Some(neighbors) = edges.get(&current_node) {
-                for neighbor in neighbors {
-                    if !path.contains(neighbor) {
-                        let mut new_path = path.clone();
-                        new_path.push(neighbor.clone());
-                        queue.push((neighbor.clone(), new_path));
-                    }
-                }
-            }
+impl Default for Params {
+    fn default() -> Params {
+        Params {
+            start: "you".to_string(),
+            server: "svr".to_string(),
+            target: "out".to_string(),
+            via: vec!["fft".to_string(), "dac".to_string()],
         }
-    } else {
-        // Recursive function with memoization to count paths between two nodes
-        fn count_paths(
-            curr: &str,
-            target: &str,
-            edges: &HashMap<String, Vec<String>>,
-            memo: &mut HashMap<String, i64>,
-        ) -> i64 {
-            if curr == target {
-                return 1;
-            }
-            if let Some(&count) = memo.get(curr) {
-                return count;
-            }
+    }
+}
 
-            let mut total = 0;
-            if let Some(neighbors) = edges.get(curr) {
-                for neighbor in neighbors {
-                    total += count_paths(neighbor, target, edges, memo);
-                }
+pub struct Day11;
+
+impl Solution for Day11 {
+    /// The devices, with an edge from each device to every device its outputs feed.
+    type Input = Graph;
+    type Params = Params;
+
+    const DAY: u8 = 11;
+    const TITLE: &'static str = "Reactor";
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        // each line is a node with outgoing edges to other nodes
+        let mut graph = Graph::new();
+        for line in lines(input) {
+            let (key, outputs) = parse::key_value(input, line, ':')?;
+            let from = graph.node(key);
+            for output in outputs.split_whitespace() {
+                let to = graph.node(output);
+                graph.add_edge(from, to);
             }
-
-            memo.insert(curr.to_string(), total);
-            total
         }
+        Ok(graph)
+    }
 
-        let get_segment_count = |start: &str, end: &str| -> i64 {
-            let mut memo = HashMap::new();
-            count_paths(start, end, &edges, &mut memo)
-        };
-
-        let start = "svr";
-        let target = "out";
-        let mid1 = "fft";
-        let mid2 = "dac";
-
-        // Case 1: svr -> fft -> dac -> out
-        let leg1 = get_segment_count(start, mid1);
-        let leg2 = get_segment_count(mid1, mid2);
-        let leg3 = get_segment_count(mid2, target);
-        result += leg1 * leg2 * leg3;
+    fn part1(&self, graph: &Self::Input, params: &Params) -> Result<Answer> {
+        count_paths(graph, &params.start, &params.target, &[])
+    }
 
-        // Case 2: svr -> dac -> fft -> out
-        let leg1 = get_segment_count(start, mid2);
-        let leg2 = get_segment_count(mid2, mid1);
-        let leg3 = get_segment_count(mid1, target);
-        result += leg1 * leg2 * leg3;
+    fn part2(&self, graph: &Self::Input, params: &Params) -> Option<Result<Answer>> {
+        // paths from the server rack to the target that pass every configured waypoint
+        let via: Vec<&str> = params.via.iter().map(String::as_str).collect();
+        Some(count_paths(graph, &params.server, &params.target, &via))
     }
+}
 
-    result
+/// Number of paths from device `start` to device `target` that pass through every device in `via`,
+/// in any order. Devices that do not appear in the graph have no paths.
+pub fn count_paths(graph: &Graph, start: &str, target: &str, via: &[&str]) -> Result<Answer> {
+    let (Some(start), Some(target)) = (graph.id(start), graph.id(target)) else {
+        return Ok(Answer::from(0));
+    };
+    let Some(via) = via.iter().map(|name| graph.id(name)).collect::<Option<Vec<usize>>>() else {
+        return Ok(Answer::from(0));
+    };
+    Ok(graph.count_paths_via(start, target, &via)?.into())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::repo_path;
-    use std::fs;
-
-    const EXAMPLE_ANSWER_PART1: i64 = 5;
-    const INPUT_ANSWER_PART1: i64 = 791;
-    const EXAMPLE_ANSWER_PART2: i64 = 2;
-    const INPUT_ANSWER_PART2: i64 = 520476725037672;
-    const DAY: &str = "day11";
 
-    #[test]
-    fn example_part1() {
-        let path = repo_path(&["inputs", DAY, "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part1(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART1);
-    }
-
-    #[test]
-    fn input_part1() {
-        let path = repo_path(&["inputs", DAY, "input.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part1(&input).unwrap();
-        assert_eq!(ans, INPUT_ANSWER_PART1);
-    }
-
-    #[test]
-    fn example_part2() {
-        let path = repo_path(&["inputs", DAY, "example2.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part2(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART2);
+    fn part1(input: &str, params: &Params) -> Result<Answer> {
+        Day11.part1(&Day11.parse(input)?, params)
     }
 
     #[test]
-    fn input_part2() {
-        let path = repo_path(&["inputs", DAY, "input.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part2(&input).unwrap();
-        assert_eq!(ans, INPUT_ANSWER_PART2);
+    fn cyclic_inputs_name_the_cycle() {
+        let err = part1("you: aaa\naaa: bbb out\nbbb: aaa\n", &Params::default()).unwrap_err();
+        assert_eq!(err.to_string(), "graph has a cycle: aaa -> bbb -> aaa");
+
+        // a loop that no path from `you` to `out` runs through is harmless
+        assert_eq!(part1("you: out\naaa: bbb\nbbb: aaa\n", &Params::default()).unwrap(), Answer::from(1));
+        let params = Params { start: "me".to_string(), ..Params::default() };
+        assert_eq!(part1("me: out\n", &params).unwrap(), Answer::from(1));
     }
 }
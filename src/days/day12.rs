@@ -1,94 +1,275 @@
+use crate::answer::Answer;
+use crate::days::Solution;
+use crate::grid::Grid;
+use crate::ilp::{self, Pending, Relation};
+use crate::{lines, parse, ParseError};
 use anyhow::Result;
-use good_lp::{constraint, default_solver, variable, variables, Expression, ProblemVariables, SolverModel};
+use serde::Deserialize;
+use std::collections::HashSet;
 
-pub fn part1(input: &str) -> Result<i64> {
-    Ok(parse_numbers(input))
+/// The present shapes and the regions under the trees.
+pub struct Farm {
+    /// The filled cells of each shape, by shape index.
+    pub shapes: Vec<Vec<(usize, usize)>>,
+    pub regions: Vec<Region>,
 }
 
+/// A `x` by `y` region and how many presents of each shape must fit in it.
 #[derive(Debug)]
-struct Region {
-    x: i64,
-    y: i64,
-    amount: Vec<i32>,
+pub struct Region {
+    pub x: usize,
+    pub y: usize,
+    pub amount: Vec<usize>,
 }
 
-fn parse_numbers(input: &str) -> i64 {
-    let mut score = 0;
-    // split at \n\n
-    let sections: Vec<&str> = input.split("\n\n").collect();
+/// How part 1 decides whether the presents fit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Solver {
+    /// The presents fit when their cells take up less than the whole region. Enough for the real
+    /// inputs, whose regions are either roomy or hopelessly full, but it counts one region too
+    /// many on the example.
+    #[default]
+    Area,
+    /// Area and bounding-box checks, then a backtracking search for the regions they leave open.
+    Search,
+    /// An integer linear program over every placement, for the backend picked at build time.
+    Ilp,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    pub solver: Solver,
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Farm;
+    type Params = Params;
+
+    const DAY: u8 = 12;
+    const TITLE: &'static str = "Christmas Tree Farm";
+    const HAS_PART2: bool = false;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_farm(input)
+    }
+
+    fn part1(&self, farm: &Self::Input, params: &Params) -> Result<Answer> {
+        let mut fitting = 0;
+        for region in &farm.regions {
+            let fits = match params.solver {
+                Solver::Area => fits_by_area(region, &farm.shapes),
+                Solver::Search => fits(region, &farm.shapes),
+                Solver::Ilp => fits_ilp(region, &farm.shapes)?,
+            };
+            fitting += usize::from(fits);
+        }
+        Ok(fitting.into())
+    }
+
+    fn models<'a>(&self, farm: &'a Self::Input, _params: &'a Params) -> Vec<Pending<'a>> {
+        let models = farm.regions.iter().enumerate();
+        models.map(|(i, region)| Pending::new(format!("region-{:03}", i + 1), move || tiling_model(region, &farm.shapes))).collect()
+    }
+}
+
+fn parse_farm(input: &str) -> Result<Farm> {
+    // split at blank lines
+    let sections: Vec<&str> = parse::sections(input).collect();
+    let Some((&last, shapes)) = sections.split_last() else {
+        return Err(ParseError::at(input, "", "shapes followed by regions").into());
+    };
+    let shapes: Vec<Vec<(usize, usize)>> = shapes
+        .iter()
+        .map(|section| {
+            // skip the `0:` header line
+            let body = section.split_once('\n').map_or("", |(_, body)| body);
+            let shape = Grid::parse(input, body, "`#` or `.`", |c| match c {
+                '#' => Some(true),
+                '.' => Some(false),
+                _ => None,
+            })?;
+            Ok(shape.iter().filter(|&(_, &filled)| filled).map(|(p, _)| p).collect())
+        })
+        .collect::<Result<_, ParseError>>()?;
     // the last section is the regions
-    let regions: Vec<Region> = sections
-        .last()
-        .unwrap()
-        .split("\n")
+    let regions = lines(last)
         .map(|line| {
-            let parts: Vec<&str> = line.split(":").collect();
-            let xy: Vec<&str> = parts[0].split("x").collect();
-            let x: i64 = xy[0].parse().unwrap();
-            let y: i64 = xy[1].parse().unwrap();
-            let amount: Vec<i32> = parts[1]
-                .split(" ")
-                .filter(|string| string.trim().len() > 0)
-                .map(|s| s.trim().parse().unwrap())
-                .collect();
-            Region { x, y, amount }
+            let (size, amounts) = parse::key_value(input, line, ':')?;
+            let (x, y) = parse::pair(input, size, 'x', "a region size like `12x5`")?;
+            let amount = parse::words(input, amounts, "a present count")?;
+            if let Some(extra) = amounts.split_whitespace().nth(shapes.len()) {
+                return Err(ParseError::at(input, extra, format!("at most {} present counts", shapes.len())));
+            }
+            Ok(Region { x, y, amount })
         })
-        .collect();
-    let shapes: Vec<Vec<(usize, usize)>> = sections
-        .iter()
-        .take(sections.len() - 1)
-        .map(|section| {
-            section
-                .lines()
-                .skip(1) // skip the first line
-                .enumerate()
-                .flat_map(|(y, line)| {
-                    line.chars().enumerate().filter_map(move |(x, c)| {
-                        if c == '#' {
-                            Some((x, y))
-                        } else { None }
+        .collect::<Result<_, ParseError>>()?;
+    Ok(Farm { shapes, regions })
+}
+
+/// A shape turned or flipped into place: the region cells it covers.
+#[derive(Clone, Debug)]
+struct Placement {
+    shape: usize,
+    cells: Vec<(usize, usize)>,
+}
+
+/// The count of each shape the region asks for, with shapes it does not mention, and shapes
+/// without cells that need no room, at zero.
+fn counts(region: &Region, shapes: &[Vec<(usize, usize)>]) -> Vec<usize> {
+    let wanted = |s: usize| if shapes[s].is_empty() { 0 } else { region.amount.get(s).map_or(0, |&n| n) };
+    (0..shapes.len()).map(wanted).collect()
+}
+
+/// Whether the presents, counted by area alone, leave at least one cell of the region free.
+fn fits_by_area(region: &Region, shapes: &[Vec<(usize, usize)>]) -> bool {
+    let area: usize = counts(region, shapes).iter().zip(shapes).map(|(&n, shape)| n * shape.len()).sum();
+    area < region.x * region.y
+}
+
+/// Whether every present fits in the region, turned or flipped as needed and never overlapping.
+///
+/// Most regions are settled without searching: too little area rules a region out, and room for
+/// one bounding box per present rules it in. The rest are searched cell by cell in reading order,
+/// either leaving the first free cell empty (while there is area to spare) or covering it with a
+/// present; that is exponential in the worst case, but small regions like the example's take
+/// milliseconds.
+fn fits(region: &Region, shapes: &[Vec<(usize, usize)>]) -> bool {
+    let (w, h) = (region.x, region.y);
+    let counts = counts(region, shapes);
+    let area: usize = counts.iter().zip(shapes).map(|(&n, shape)| n * shape.len()).sum();
+    if area > w * h {
+        return false;
+    }
+    let side = (0..shapes.len())
+        .filter(|&s| counts[s] > 0)
+        .flat_map(|s| shapes[s].iter().map(|&(x, y)| x.max(y) + 1))
+        .max()
+        .unwrap_or(1);
+    if (w / side) * (h / side) >= counts.iter().sum() {
+        return true;
+    }
+
+    // presents turn freely, so scan along the shorter side: fewer cells are half decided at a time
+    let (w, h) = (w.min(h), w.max(h));
+    let orientations: Vec<Vec<Vec<(i32, i32)>>> = shapes.iter().map(|shape| orientations(shape)).collect();
+    let mut search = Search { grid: Grid::new(w, h, false), counts, orientations, dead_ends: HashSet::new() };
+    search.from(0, w * h - area)
+}
+
+/// The state of [`fits`]' search. Every cell before the one being decided is settled, so the
+/// cells from there on and the presents left describe the rest of the search, and a state that
+/// failed once is not searched again.
+struct Search {
+    grid: Grid<bool>,
+    counts: Vec<usize>,
+    orientations: Vec<Vec<Vec<(i32, i32)>>>,
+    dead_ends: HashSet<(usize, Vec<usize>, Vec<bool>)>,
+}
+
+impl Search {
+    /// Whether the presents left fit, with cells before `from` settled and `slack` cells still
+    /// allowed to stay empty.
+    fn from(&mut self, from: usize, slack: usize) -> bool {
+        if self.counts.iter().all(|&n| n == 0) {
+            return true;
+        }
+        let w = self.grid.width();
+        let cells = w * self.grid.height();
+        let Some(next) = (from..cells).find(|&i| !self.grid[(i % w, i / w)]) else {
+            return false;
+        };
+        let state = (next, self.counts.clone(), (next..cells).map(|i| self.grid[(i % w, i / w)]).collect());
+        if self.dead_ends.contains(&state) {
+            return false;
+        }
+        let found = self.decide(next, slack);
+        if !found {
+            self.dead_ends.insert(state);
+        }
+        found
+    }
+
+    /// Cover cell `next` with a present, or failing that leave it empty.
+    fn decide(&mut self, next: usize, slack: usize) -> bool {
+        let w = self.grid.width();
+        let (x, y) = ((next % w) as i32, (next / w) as i32);
+        for shape in 0..self.counts.len() {
+            if self.counts[shape] == 0 {
+                continue;
+            }
+            for o in 0..self.orientations[shape].len() {
+                let cells = &self.orientations[shape][o];
+                // the orientation's first cell in reading order goes on the free cell
+                let (ax, ay) = *cells.iter().min_by_key(|&&(cx, cy)| (cy, cx)).unwrap();
+                let placed: Option<Vec<(usize, usize)>> = cells
+                    .iter()
+                    .map(|&(cx, cy)| {
+                        let (px, py) = (x + cx - ax, y + cy - ay);
+                        let p = (usize::try_from(px).ok()?, usize::try_from(py).ok()?);
+                        (self.grid.get(p.0, p.1) == Some(&false)).then_some(p)
                     })
-                })
-                .collect()
-        })
-        .collect();
-
-    for region in regions {
-        // check if we can pack all shapes into the region, they have to fit exactly, can be rotated or flipped
-        // also the amounts have to match, e.g. if region.amount = [2, 1] and shapes = [shape1, shape2]
-        // then we need to fit shape1 twice and shape2 once into the region
-
-        // quick check: total area of shapes must be less area of region
-        let region_area = region.x as usize * region.y as usize;
-        let mut shapes_area = 0;
-        for (shape_idx, shape) in shapes.iter().enumerate() {
-            let shape_area = shape.len();
-            let amount = if shape_idx < region.amount.len() { region.amount[shape_idx] } else { 0 };
-            shapes_area += shape_area * (amount as usize);
+                    .collect();
+                let Some(placed) = placed else {
+                    continue;
+                };
+                placed.iter().for_each(|&p| self.grid[p] = true);
+                self.counts[shape] -= 1;
+                let found = self.from(next + 1, slack);
+                self.counts[shape] += 1;
+                placed.iter().for_each(|&p| self.grid[p] = false);
+                if found {
+                    return true;
+                }
+            }
         }
-        if shapes_area >= region_area {
-            println!("Region {:?} cannot be tiled: area mismatch", region);
-            continue;
+
+        if slack == 0 {
+            return false;
         }
-        //if can_tile_region(&region, &shapes) {
-        score += 1;
-        //} else {
-        //    println!("Region {:?} cannot be tiled", region);
-        // }
+        let cell = (x as usize, y as usize);
+        self.grid[cell] = true;
+        let found = self.from(next + 1, slack - 1);
+        self.grid[cell] = false;
+        found
     }
+}
 
-    score
+/// [`fits`] as an integer linear program: a binary variable per placement, each cell covered at
+/// most once and each shape placed exactly as often as asked.
+fn fits_ilp(region: &Region, shapes: &[Vec<(usize, usize)>]) -> Result<bool> {
+    Ok(tiling_model(region, shapes).solve()?.is_some())
 }
 
-#[derive(Clone, Debug)]
-struct Placement {
-    shape_idx: usize,
-    cells: Vec<(usize, usize)>, // absolute cells covered in the region grid
+fn tiling_model(region: &Region, shapes: &[Vec<(usize, usize)>]) -> ilp::Model {
+    let (w, h) = (region.x, region.y);
+    let counts = counts(region, shapes);
+    let placements = placements(w, h, shapes, &counts);
+
+    let mut model = ilp::Model::new();
+    let vars: Vec<usize> = (0..placements.len()).map(|p| model.variable(format!("p{p}"), 0, Some(1))).collect();
+    let mut covering = Grid::new(w, h, vec![]);
+    for (p, placement) in placements.iter().enumerate() {
+        placement.cells.iter().for_each(|&cell| covering[cell].push(vars[p]));
+    }
+    for ((x, y), covered_by) in covering.iter() {
+        if covered_by.len() > 1 {
+            model.constraint(format!("x{x}y{y}"), covered_by.iter().map(|&v| (v, 1)), Relation::AtMost, 1);
+        }
+    }
+    for (shape, &count) in counts.iter().enumerate() {
+        let used = placements.iter().zip(&vars).filter(|(p, _)| p.shape == shape).map(|(_, &v)| (v, 1));
+        model.constraint(format!("s{shape}"), used, Relation::Equal, count as i64);
+    }
+    model
 }
 
 fn normalize(mut cells: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
-    let min_x = cells.iter().map(|(x, _)| *x).min().unwrap();
-    let min_y = cells.iter().map(|(_, y)| *y).min().unwrap();
+    let min_x = cells.iter().map(|(x, _)| *x).min().unwrap_or(0);
+    let min_y = cells.iter().map(|(_, y)| *y).min().unwrap_or(0);
     for (x, y) in cells.iter_mut() {
         *x -= min_x;
         *y -= min_y;
@@ -97,144 +278,66 @@ fn normalize(mut cells: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
     cells
 }
 
+/// The distinct rotations and reflections of a shape, each moved to touch both axes.
 fn orientations(shape: &[(usize, usize)]) -> Vec<Vec<(i32, i32)>> {
     let pts: Vec<(i32, i32)> = shape.iter().map(|&(x, y)| (x as i32, y as i32)).collect();
 
     // 8 symmetries: 4 rotations of (x,y) and 4 rotations of flipped (x,-y)
-    let mut outs: Vec<Vec<(i32, i32)>> = Vec::new();
-
     let transforms = |(x, y): (i32, i32)| -> [(i32, i32); 8] {
-        // rotations
-        let r0 = (x, y);
-        let r90 = (-y, x);
-        let r180 = (-x, -y);
-        let r270 = (y, -x);
-        // flip across x-axis then rotate
-        let f0 = (x, -y);
-        let f90 = (y, x);
-        let f180 = (-x, y);
-        let f270 = (-y, -x);
-        [r0, r90, r180, r270, f0, f90, f180, f270]
+        [(x, y), (-y, x), (-x, -y), (y, -x), (x, -y), (y, x), (-x, y), (-y, -x)]
     };
 
-    for k in 0..8 {
-        let mut o: Vec<(i32, i32)> = pts.iter().map(|&p| transforms(p)[k]).collect();
-        o = normalize(o);
-        outs.push(o);
-    }
-
-    // dedup
+    let mut outs: Vec<Vec<(i32, i32)>> = (0..8).map(|k| normalize(pts.iter().map(|&p| transforms(p)[k]).collect())).collect();
     outs.sort();
     outs.dedup();
     outs
 }
 
-fn generate_placements(region_w: usize, region_h: usize, shapes: &[Vec<(usize, usize)>], amounts: &[i32]) -> Vec<Placement> {
+/// Every way to put a wanted shape inside a `w` by `h` region.
+fn placements(w: usize, h: usize, shapes: &[Vec<(usize, usize)>], counts: &[usize]) -> Vec<Placement> {
     let mut placements = Vec::new();
-
-    for (shape_idx, shape) in shapes.iter().enumerate() {
-        if shape_idx >= amounts.len() || amounts[shape_idx] == 0 {
+    for (shape, cells) in shapes.iter().enumerate() {
+        if counts[shape] == 0 {
             continue;
         }
-        let os = orientations(shape);
-
-        for o in os {
-            let max_x = o.iter().map(|(x, _)| *x).max().unwrap() as i32;
-            let max_y = o.iter().map(|(_, y)| *y).max().unwrap() as i32;
-
-            let w = region_w as i32;
-            let h = region_h as i32;
-
-            for ox in 0..=(w - 1 - max_x) {
-                for oy in 0..=(h - 1 - max_y) {
-                    let mut abs_cells = Vec::with_capacity(o.len());
-                    let mut ok = true;
-                    for &(dx, dy) in &o {
-                        let ax = ox + dx;
-                        let ay = oy + dy;
-                        if ax < 0 || ay < 0 || ax >= w || ay >= h {
-                            ok = false;
-                            break;
-                        }
-                        abs_cells.push((ax as usize, ay as usize));
-                    }
-                    if ok {
-                        placements.push(Placement { shape_idx, cells: abs_cells });
-                    }
+        for o in orientations(cells) {
+            let max_x = o.iter().map(|(x, _)| *x).max().unwrap() as usize;
+            let max_y = o.iter().map(|(_, y)| *y).max().unwrap() as usize;
+            for ox in 0..w.saturating_sub(max_x) {
+                for oy in 0..h.saturating_sub(max_y) {
+                    let cells = o.iter().map(|&(dx, dy)| (ox + dx as usize, oy + dy as usize)).collect();
+                    placements.push(Placement { shape, cells });
                 }
             }
         }
     }
-
     placements
 }
 
-fn can_tile_region(region: &Region, shapes: &[Vec<(usize, usize)>]) -> bool {
-    let w = region.x as usize;
-    let h = region.y as usize;
-
-    let placements = generate_placements(w, h, shapes, &region.amount);
-    if placements.is_empty() {
-        return false;
-    }
-
-    // Build ILP
-    let mut vars: ProblemVariables = variables!();
-    let place_vars = vars.add_vector(variable().binary(), placements.len());
-
-    // cell constraints: each cell covered exactly once
-    let mut model = vars.minimise(0).using(default_solver);
-
-    // For each cell (cx, cy): sum(place_vars[p] if p covers cell) == 1
-    for cy in 0..h {
-        for cx in 0..w {
-            let mut expr: Expression = 0.into();
-            for (pi, p) in placements.iter().enumerate() {
-                if p.cells.iter().any(|&(x, y)| x == cx && y == cy) {
-                    expr += place_vars[pi];
-                }
-            }
-            model = model.with(constraint!(expr <= 1));
-        }
-    }
-
-    // amount constraints: each shape i used exactly region.amount[i]
-    for (si, &amt) in region.amount.iter().enumerate() {
-        let mut expr: Expression = 0.into();
-        for (pi, p) in placements.iter().enumerate() {
-            if p.shape_idx == si {
-                expr += place_vars[pi];
-            }
-        }
-        model = model.with(constraint!(expr == amt));
-    }
-
-    model.solve().is_ok()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::repo_path;
-    use std::fs;
+    use crate::days::testing::{assert_models_named, assert_solvers_agree, example, parse_error};
 
-    const EXAMPLE_ANSWER_PART1: i64 = 2;
-    const INPUT_ANSWER_PART1: i64 = 487;
-    const DAY: &str = "day12";
+    #[test]
+    fn malformed_input_reports_its_location() {
+        let err = parse_error(&Day12, "0:\n#\n\n3x-3: 1\n");
+        assert_eq!((err.line, err.column, err.found.as_str()), (4, 3, "-3"));
+        let err = parse_error(&Day12, "0:\n#\n\n1x1: -1\n");
+        assert_eq!((err.line, err.column, err.found.as_str()), (4, 6, "-1"));
+        let err = parse_error(&Day12, "4x4: 1\n");
+        assert_eq!((err.column, err.found.as_str(), err.expected.as_str()), (6, "1", "at most 0 present counts"));
+    }
 
     #[test]
-    fn example_part1() {
-        let path = repo_path(&["inputs", DAY, "example.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part1(&input).unwrap();
-        assert_eq!(ans, EXAMPLE_ANSWER_PART1);
+    fn solvers_agree_on_the_example() {
+        assert_solvers_agree(&Day12, 1, &["search"], Answer::from(2));
+        // the third region has the area but not the shape for its presents
+        assert_eq!(Day12.part1(&example(&Day12), &Params::default()).unwrap(), Answer::from(3));
     }
 
     #[test]
-    fn input_part1() {
-        let path = repo_path(&["inputs", DAY, "input.txt"]);
-        let input = fs::read_to_string(path).expect("missing example file");
-        let ans = part1(&input).unwrap();
-        assert_eq!(ans, INPUT_ANSWER_PART1);
+    fn names_a_model_per_region() {
+        assert_models_named(&Day12, "region", 3);
     }
 }
@@ -1,3 +1,10 @@
+use crate::answer::Answer;
+use crate::ilp::Pending;
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::any::Any;
+
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -6,6 +13,237 @@ pub mod day05;
 pub mod day06;
 pub mod day07;
 pub mod day08;
-mod day09;
-mod day10;
-mod day11;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+
+/// A single day's puzzle: a parse step producing `Input`, shared by both parts.
+///
+/// Implement this on a unit struct in `src/days/dayXX.rs` and add it to [`REGISTRY`].
+pub trait Solution {
+    type Input: 'static;
+
+    /// Constants the puzzle text fixes but the examples may change, such as how many connections
+    /// to make. `Default` gives the values for the real input; [`NoParams`] if there are none.
+    type Params: Default + DeserializeOwned + 'static;
+
+    const DAY: u8;
+    const TITLE: &'static str;
+    /// `false` for days that only have one part, which keep the default [`Solution::part2`].
+    const HAS_PART2: bool = true;
+
+    fn parse(&self, input: &str) -> Result<Self::Input>;
+
+    fn part1(&self, input: &Self::Input, params: &Self::Params) -> Result<Answer>;
+
+    /// `None` for days that only have one part (e.g. day 12).
+    fn part2(&self, _input: &Self::Input, _params: &Self::Params) -> Option<Result<Answer>> {
+        None
+    }
+
+    /// Notes on how the parts went beyond their answers, such as which input lines could not be
+    /// solved and why; printed by `aoc run --report`. Built from what each part that ran returned,
+    /// by part number, so nothing is solved twice. Most days have nothing to add.
+    fn report(&self, _input: &Self::Input, _outcomes: &[(u8, &Result<Answer>)]) -> Vec<String> {
+        vec![]
+    }
+
+    /// The integer linear programs behind the day's ILP solver, one per machine, region or the like,
+    /// for `aoc export` to write out. Empty for days that do not use one.
+    fn models<'a>(&self, _input: &'a Self::Input, _params: &'a Self::Params) -> Vec<Pending<'a>> {
+        vec![]
+    }
+}
+
+/// The parameters of a day that has none. Any override is rejected.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NoParams {}
+
+/// Object-safe view of a [`Solution`], so the registry can hold every day in one list.
+pub trait Day: Sync {
+    fn day(&self) -> u8;
+    fn title(&self) -> &'static str;
+    fn has_part2(&self) -> bool;
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>>;
+    /// The default parameters with `overrides` applied on top, e.g. `connections = 10`.
+    fn params(&self, overrides: &toml::Table) -> Result<Box<dyn Any>>;
+    fn part1(&self, parsed: &dyn Any, params: &dyn Any) -> Result<Answer>;
+    fn part2(&self, parsed: &dyn Any, params: &dyn Any) -> Option<Result<Answer>>;
+    fn report(&self, parsed: &dyn Any, outcomes: &[(u8, &Result<Answer>)]) -> Result<Vec<String>>;
+    fn models<'a>(&self, parsed: &'a dyn Any, params: &'a dyn Any) -> Result<Vec<Pending<'a>>>;
+}
+
+impl<S: Solution + Sync> Day for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        S::TITLE
+    }
+
+    fn has_part2(&self) -> bool {
+        S::HAS_PART2
+    }
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>> {
+        Ok(Box::new(Solution::parse(self, input)?))
+    }
+
+    fn params(&self, overrides: &toml::Table) -> Result<Box<dyn Any>> {
+        let params: S::Params = toml::Value::Table(overrides.clone())
+            .try_into()
+            .with_context(|| format!("invalid parameters for day {:02}", S::DAY))?;
+        Ok(Box::new(params))
+    }
+
+    fn part1(&self, parsed: &dyn Any, params: &dyn Any) -> Result<Answer> {
+        Solution::part1(self, downcast::<S::Input>(S::DAY, parsed)?, downcast::<S::Params>(S::DAY, params)?)
+    }
+
+    fn part2(&self, parsed: &dyn Any, params: &dyn Any) -> Option<Result<Answer>> {
+        match (downcast::<S::Input>(S::DAY, parsed), downcast::<S::Params>(S::DAY, params)) {
+            (Ok(input), Ok(params)) => Solution::part2(self, input, params),
+            (Err(e), _) | (_, Err(e)) => Some(Err(e)),
+        }
+    }
+
+    fn report(&self, parsed: &dyn Any, outcomes: &[(u8, &Result<Answer>)]) -> Result<Vec<String>> {
+        Ok(Solution::report(self, downcast::<S::Input>(S::DAY, parsed)?, outcomes))
+    }
+
+    fn models<'a>(&self, parsed: &'a dyn Any, params: &'a dyn Any) -> Result<Vec<Pending<'a>>> {
+        Ok(Solution::models(self, downcast::<S::Input>(S::DAY, parsed)?, downcast::<S::Params>(S::DAY, params)?))
+    }
+}
+
+fn downcast<T: 'static>(day: u8, value: &dyn Any) -> Result<&T> {
+    value
+        .downcast_ref::<T>()
+        .ok_or_else(|| anyhow::anyhow!("day {day:02}: expected a {}", std::any::type_name::<T>()))
+}
+
+/// Every implemented day, ordered by day number.
+pub static REGISTRY: &[&dyn Day] = &[
+    &day01::Day01,
+    &day02::Day02,
+    &day03::Day03,
+    &day04::Day04,
+    &day05::Day05,
+    &day06::Day06,
+    &day07::Day07,
+    &day08::Day08,
+    &day09::Day09,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+];
+
+/// Look up a day in the [`REGISTRY`].
+pub fn get(day: u8) -> Option<&'static dyn Day> {
+    REGISTRY.iter().copied().find(|d| d.day() == day)
+}
+
+/// Helpers shared by the days' tests.
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::Solution;
+    use crate::answer::Answer;
+    use crate::{repo_path, ParseError};
+
+    /// `inputs/dayXX/example.txt`, parsed by `day`.
+    pub fn example<S: Solution>(day: &S) -> S::Input {
+        let path = repo_path(&["inputs", &format!("day{:02}", S::DAY), "example.txt"]);
+        let input = std::fs::read_to_string(path).expect("missing example file");
+        Solution::parse(day, &input).unwrap()
+    }
+
+    /// Check that `day` builds `count` models for its example, named `<prefix>-001` on, and that
+    /// they are written out under their names.
+    pub fn assert_models_named<S: Solution>(day: &S, prefix: &str, count: usize) {
+        let (input, params) = (example(day), S::Params::default());
+        let models = Solution::models(day, &input, &params);
+        let names: Vec<&str> = models.iter().map(|m| m.name.as_str()).collect();
+        let expected: Vec<String> = (1..=count).map(|i| format!("{prefix}-{i:03}")).collect();
+        assert_eq!(names, expected);
+        let first = &expected[0];
+        let model = models.into_iter().next().unwrap().build();
+        assert!(model.to_lp(first).starts_with(&format!("\\ {first}\nMinimize\n")));
+    }
+
+    /// Check that `part` of `day` gives `expected` on its example with each of `solvers`, and with
+    /// `ilp` too when an ILP backend is built in.
+    pub fn assert_solvers_agree<S: Solution>(day: &S, part: u8, solvers: &[&str], expected: Answer) {
+        let input = example(day);
+        let ilp = cfg!(feature = "ilp").then_some("ilp");
+        for solver in solvers.iter().copied().chain(ilp) {
+            let params: S::Params = toml::from_str(&format!("solver = {solver:?}")).unwrap();
+            let answer = match part {
+                1 => Solution::part1(day, &input, &params),
+                _ => Solution::part2(day, &input, &params).unwrap(),
+            };
+            assert_eq!(answer.unwrap(), expected, "solver {solver}");
+        }
+    }
+
+    /// The [`ParseError`] `day` reports for `input`.
+    pub fn parse_error<S: Solution>(day: &S, input: &str) -> ParseError {
+        match Solution::parse(day, input) {
+            Ok(_) => panic!("{input:?} parsed"),
+            Err(err) => err.downcast::<ParseError>().expect("not a ParseError"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_sorted_and_unique() {
+        let days: Vec<u8> = REGISTRY.iter().map(|d| d.day()).collect();
+        let mut sorted = days.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(days, sorted);
+    }
+
+    /// Part 1 of `day` on `input`, with the parameters given as TOML, e.g. `connections = 10`.
+    fn part1(day: u8, input: &str, params: &str) -> Result<Answer> {
+        let day = get(day).unwrap();
+        let params = day.params(&params.parse::<toml::Table>()?)?;
+        day.part1(day.parse(input)?.as_ref(), params.as_ref())
+    }
+
+    #[test]
+    fn registry_runs_a_day() {
+        assert_eq!(part1(1, "R50\n", "").unwrap(), Answer::from(1));
+    }
+
+    #[test]
+    fn days_without_params_reject_overrides() {
+        assert!(toml::from_str::<NoParams>("").is_ok());
+        assert!(toml::from_str::<NoParams>("anything = 1").is_err());
+        assert!(part1(4, "@\n", "").is_ok());
+        assert!(part1(4, "@\n", "anything = 1").is_err());
+    }
+
+    #[test]
+    fn days_without_an_ilp_build_no_models() {
+        let input = testing::example(&day05::Day05);
+        assert!(Solution::models(&day05::Day05, &input, &NoParams {}).is_empty());
+    }
+
+    #[test]
+    fn days_say_whether_they_have_part2() {
+        for &day in REGISTRY {
+            let path = crate::repo_path(&["inputs", &format!("day{:02}", day.day()), "example.txt"]);
+            let parsed = day.parse(&std::fs::read_to_string(path).unwrap()).unwrap();
+            let params = day.params(&toml::Table::new()).unwrap();
+            let part2 = day.part2(parsed.as_ref(), params.as_ref());
+            assert_eq!(part2.is_some(), day.has_part2(), "day {:02}", day.day());
+        }
+    }
+}
@@ -0,0 +1,350 @@
+//! Integer points, distance metrics and bounding boxes.
+//!
+//! Everything is exact: distances are compared squared rather than through a square root, and the
+//! segment tests work on fractions instead of floats. Differences of coordinates can exceed `i64`,
+//! so the metrics and sizes are computed on wider types.
+
+use crate::answer::Number;
+use num_bigint::BigInt;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+/// Component-wise arithmetic and the three metrics, written once for both point types.
+macro_rules! point {
+    ($point:ident { $($c:ident),+ }) => {
+        impl $point {
+            pub const ORIGIN: $point = $point { $($c: 0),+ };
+
+            pub const fn new($($c: i64),+) -> $point {
+                $point { $($c),+ }
+            }
+
+            /// Squared Euclidean distance; exact, and orders pairs the same way the real distance does.
+            pub fn distance_squared(self, other: $point) -> Number {
+                let square = |d: u64| Number::from(u128::from(d) * u128::from(d));
+                Number::ZERO $(+ square(self.$c.abs_diff(other.$c)))+
+            }
+
+            pub fn manhattan(self, other: $point) -> u128 {
+                0 $(+ u128::from(self.$c.abs_diff(other.$c)))+
+            }
+
+            pub fn chebyshev(self, other: $point) -> u64 {
+                0 $(.max(self.$c.abs_diff(other.$c)))+
+            }
+
+            /// Component-wise minimum.
+            pub fn min(self, other: $point) -> $point {
+                $point { $($c: self.$c.min(other.$c)),+ }
+            }
+
+            /// Component-wise maximum.
+            pub fn max(self, other: $point) -> $point {
+                $point { $($c: self.$c.max(other.$c)),+ }
+            }
+        }
+
+        impl Add for $point {
+            type Output = $point;
+
+            fn add(self, other: $point) -> $point {
+                $point { $($c: self.$c + other.$c),+ }
+            }
+        }
+
+        impl Sub for $point {
+            type Output = $point;
+
+            fn sub(self, other: $point) -> $point {
+                $point { $($c: self.$c - other.$c),+ }
+            }
+        }
+
+        impl Neg for $point {
+            type Output = $point;
+
+            fn neg(self) -> $point {
+                $point { $($c: -self.$c),+ }
+            }
+        }
+
+        impl Mul<i64> for $point {
+            type Output = $point;
+
+            fn mul(self, k: i64) -> $point {
+                $point { $($c: self.$c * k),+ }
+            }
+        }
+
+        impl AddAssign for $point {
+            fn add_assign(&mut self, other: $point) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $point {
+            fn sub_assign(&mut self, other: $point) {
+                *self = *self - other;
+            }
+        }
+    };
+}
+
+point!(Point2 { x, y });
+point!(Point3 { x, y, z });
+
+impl From<(i64, i64)> for Point2 {
+    fn from((x, y): (i64, i64)) -> Point2 {
+        Point2 { x, y }
+    }
+}
+
+impl From<[i64; 2]> for Point2 {
+    fn from([x, y]: [i64; 2]) -> Point2 {
+        Point2 { x, y }
+    }
+}
+
+impl From<(i64, i64, i64)> for Point3 {
+    fn from((x, y, z): (i64, i64, i64)) -> Point3 {
+        Point3 { x, y, z }
+    }
+}
+
+impl From<[i64; 3]> for Point3 {
+    fn from([x, y, z]: [i64; 3]) -> Point3 {
+        Point3 { x, y, z }
+    }
+}
+
+impl fmt::Display for Point2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl fmt::Display for Point3 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+/// An axis-aligned rectangle of whole cells, `min` and `max` included.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub min: Point2,
+    pub max: Point2,
+}
+
+impl Rect {
+    /// The rectangle with opposite corners `a` and `b`, in any order.
+    pub fn spanning(a: Point2, b: Point2) -> Rect {
+        Rect { min: a.min(b), max: a.max(b) }
+    }
+
+    /// The smallest rectangle containing every point, or `None` if there are none.
+    pub fn bounding(points: impl IntoIterator<Item = Point2>) -> Option<Rect> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Rect::spanning(first, first), |r, p| Rect { min: r.min.min(p), max: r.max.max(p) }))
+    }
+
+    pub fn width(&self) -> u128 {
+        size(self.min.x, self.max.x)
+    }
+
+    pub fn height(&self) -> u128 {
+        size(self.min.y, self.max.y)
+    }
+
+    /// Number of cells covered.
+    pub fn area(&self) -> Number {
+        Number::from(self.width()) * Number::from(self.height())
+    }
+
+    pub fn contains(&self, p: Point2) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    /// Whether some point of the segment `a`-`b`, endpoints excluded, lies strictly inside the
+    /// rectangle (not on its border). Works on exact fractions of the segment parameter.
+    pub fn segment_crosses_interior(&self, a: Point2, b: Point2) -> bool {
+        let x = slab(a.x, b.x, self.min.x, self.max.x);
+        let y = slab(a.y, b.y, self.min.y, self.max.y);
+        let (Some((x0, x1)), Some((y0, y1))) = (x, y) else {
+            return false;
+        };
+        let start = [Fraction::ZERO, x0, y0].into_iter().max().unwrap_or(Fraction::ZERO);
+        let end = [Fraction::ONE, x1, y1].into_iter().min().unwrap_or(Fraction::ONE);
+        start < end
+    }
+}
+
+/// An axis-aligned box of whole cells, `min` and `max` included.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cuboid {
+    pub min: Point3,
+    pub max: Point3,
+}
+
+impl Cuboid {
+    /// The smallest box containing every point, or `None` if there are none.
+    pub fn bounding(points: impl IntoIterator<Item = Point3>) -> Option<Cuboid> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Cuboid { min: first, max: first }, |c, p| Cuboid { min: c.min.min(p), max: c.max.max(p) }))
+    }
+
+    /// Number of cells covered.
+    pub fn volume(&self) -> Number {
+        let (min, max) = (self.min, self.max);
+        [size(min.x, max.x), size(min.y, max.y), size(min.z, max.z)].into_iter().map(Number::from).product()
+    }
+
+    pub fn contains(&self, p: Point3) -> bool {
+        self.min.min(p) == self.min && self.max.max(p) == self.max
+    }
+}
+
+/// Number of whole cells from `min` to `max`, both included.
+fn size(min: i64, max: i64) -> u128 {
+    u128::from(max.abs_diff(min)) + 1
+}
+
+/// The open range of the segment parameter `t` for which `from + t * (to - from)` lies strictly
+/// between `lo` and `hi` on one axis; unbounded if the segment runs parallel inside the slab.
+fn slab(from: i64, to: i64, lo: i64, hi: i64) -> Option<(Fraction, Fraction)> {
+    let (from, to, lo, hi) = (i128::from(from), i128::from(to), i128::from(lo), i128::from(hi));
+    let d = to - from;
+    if d == 0 {
+        return (lo < from && from < hi).then_some((Fraction::MIN, Fraction::MAX));
+    }
+    let t1 = Fraction::new(lo - from, d);
+    let t2 = Fraction::new(hi - from, d);
+    Some((t1.min(t2), t1.max(t2)))
+}
+
+/// `num / den` with `den > 0`, compared exactly by cross-multiplication.
+#[derive(Clone, Copy, Debug)]
+struct Fraction {
+    num: i128,
+    den: i128,
+}
+
+impl Fraction {
+    const ZERO: Fraction = Fraction { num: 0, den: 1 };
+    const ONE: Fraction = Fraction { num: 1, den: 1 };
+    const MIN: Fraction = Fraction { num: -1, den: 0 };
+    const MAX: Fraction = Fraction { num: 1, den: 0 };
+
+    fn new(num: i128, den: i128) -> Fraction {
+        let sign = den.signum();
+        Fraction { num: num * sign, den: den * sign }
+    }
+}
+
+impl Ord for Fraction {
+    fn cmp(&self, other: &Fraction) -> Ordering {
+        // `den == 0` stands for minus or plus infinity, which cross-multiplication handles as long
+        // as both sides are not infinite at once
+        match (self.den, other.den) {
+            (0, 0) => self.num.cmp(&other.num),
+            // differences of `i64`s can take the products past `i128`
+            _ => match (self.num.checked_mul(other.den), other.num.checked_mul(self.den)) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                _ => (BigInt::from(self.num) * other.den).cmp(&(BigInt::from(other.num) * self.den)),
+            },
+        }
+    }
+}
+
+impl PartialOrd for Fraction {
+    fn partial_cmp(&self, other: &Fraction) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Fraction {
+    fn eq(&self, other: &Fraction) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Fraction {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metrics_are_exact() {
+        let a = Point3::new(162, 817, 812);
+        let b = Point3::new(425, 690, 689);
+        assert_eq!(a.distance_squared(b), Number::from(263 * 263 + 127 * 127 + 123 * 123));
+        assert_eq!(a.manhattan(b), 263 + 127 + 123);
+        assert_eq!(a.chebyshev(b), 263);
+        // 1^2 + 1^2 = 2 and 1^2 + 0^2 = 1 round to the same integer square root but must not tie
+        let o = Point2::ORIGIN;
+        assert!(o.distance_squared(Point2::new(1, 1)) > o.distance_squared(Point2::new(1, 0)));
+
+        // coordinates far enough apart that their differences overflow `i64`
+        let (min, max) = (Point3::new(i64::MIN, i64::MIN, 0), Point3::new(i64::MAX, i64::MAX, 0));
+        let span = u128::from(u64::MAX);
+        assert_eq!(min.distance_squared(max), Number::from(span * span) + Number::from(span * span));
+        assert_eq!((min.manhattan(max), min.chebyshev(max)), (2 * span, u64::MAX));
+        let all = Rect::spanning(Point2::new(i64::MIN, i64::MIN), Point2::new(i64::MAX, i64::MAX));
+        assert_eq!(all.width(), 1 << 64);
+        assert_eq!(all.area(), Number::from(1u128 << 64) * Number::from(1u128 << 64));
+        assert!(all.segment_crosses_interior(Point2::new(i64::MIN, 0), Point2::new(i64::MAX, 0)));
+    }
+
+    #[test]
+    fn point_arithmetic() {
+        let p = Point2::new(3, -4);
+        assert_eq!(p + Point2::new(1, 1), Point2::new(4, -3));
+        assert_eq!(p - p, Point2::ORIGIN);
+        assert_eq!(-p * 2, Point2::new(-6, 8));
+        assert_eq!(Point3::from([1, 2, 3]).to_string(), "1,2,3");
+    }
+
+    #[test]
+    fn bounding_boxes() {
+        let points = [(7, 1), (11, 7), (2, 5)].map(Point2::from);
+        let rect = Rect::bounding(points).unwrap();
+        assert_eq!(rect, Rect::spanning(Point2::new(11, 7), Point2::new(2, 1)));
+        assert_eq!((rect.width(), rect.height(), rect.area()), (10, 7, Number::from(70)));
+        assert!(rect.contains(Point2::new(2, 7)) && !rect.contains(Point2::new(1, 7)));
+        assert_eq!(Rect::bounding([]), None);
+
+        let cuboid = Cuboid::bounding([Point3::new(0, 0, 0), Point3::new(1, 2, 3)]).unwrap();
+        assert_eq!(cuboid.volume(), Number::from(24));
+        assert!(cuboid.contains(Point3::new(1, 1, 1)) && !cuboid.contains(Point3::new(2, 1, 1)));
+    }
+
+    #[test]
+    fn segments_against_the_interior() {
+        let rect = Rect::spanning(Point2::new(0, 0), Point2::new(4, 4));
+        let p = Point2::new;
+        // straight through, along the border, ending on the border, and diagonally through a corner
+        assert!(rect.segment_crosses_interior(p(2, -5), p(2, 9)));
+        assert!(!rect.segment_crosses_interior(p(0, -5), p(0, 9)));
+        assert!(!rect.segment_crosses_interior(p(-3, 2), p(0, 2)));
+        assert!(rect.segment_crosses_interior(p(-3, 2), p(1, 2)));
+        assert!(!rect.segment_crosses_interior(p(-1, 1), p(1, -1)));
+        assert!(rect.segment_crosses_interior(p(-1, 2), p(3, -2)));
+    }
+}
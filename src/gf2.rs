@@ -0,0 +1,174 @@
+//! Linear equations over GF(2), where adding is XOR: the arithmetic of switches that toggle.
+
+use std::fmt;
+
+const WORD: usize = u64::BITS as usize;
+
+/// A system `A x = b` over GF(2) with a fixed number of unknowns, built one equation at a time.
+#[derive(Clone, Debug, Default)]
+pub struct System {
+    variables: usize,
+    /// Each row holds one bit per variable, followed by the right-hand side at bit `variables`.
+    rows: Vec<Vec<u64>>,
+}
+
+impl System {
+    pub fn new(variables: usize) -> System {
+        System { variables, rows: vec![] }
+    }
+
+    pub fn variables(&self) -> usize {
+        self.variables
+    }
+
+    /// Add the equation "the XOR of `terms` is `rhs`". A variable listed twice cancels out.
+    pub fn equation(&mut self, terms: impl IntoIterator<Item = usize>, rhs: bool) {
+        let mut row = vec![0; self.variables / WORD + 1];
+        for term in terms {
+            assert!(term < self.variables, "variable {term} out of range for {} variables", self.variables);
+            flip(&mut row, term);
+        }
+        if rhs {
+            flip(&mut row, self.variables);
+        }
+        self.rows.push(row);
+    }
+
+    /// A solution with as few `true` variables as possible, or `None` if the equations contradict
+    /// each other. Only the best solution so far is kept while the rest are enumerated.
+    pub fn min_weight_solution(&self) -> Result<Option<Vec<bool>>, TooManyFree> {
+        Ok(self.solutions()?.min_by_key(|x| x.iter().filter(|&&on| on).count()))
+    }
+
+    /// Every solution, one at a time; none if the equations contradict each other. Gaussian
+    /// elimination leaves some variables free and every assignment of those gives one solution,
+    /// so the count doubles with each free variable, and 64 or more are an error.
+    pub fn solutions(&self) -> Result<Solutions, TooManyFree> {
+        let n = self.variables;
+        let mut rows = self.rows.clone();
+        // reduced row echelon form: `pivots[i]` is the only variable of row `i` among the pivots
+        let mut pivots = vec![];
+        for col in 0..n {
+            let Some(found) = (pivots.len()..rows.len()).find(|&i| bit(&rows[i], col)) else {
+                continue;
+            };
+            let r = pivots.len();
+            rows.swap(r, found);
+            let pivot = rows[r].clone();
+            for (i, row) in rows.iter_mut().enumerate() {
+                if i != r && bit(row, col) {
+                    row.iter_mut().zip(&pivot).for_each(|(a, b)| *a ^= b);
+                }
+            }
+            pivots.push(col);
+        }
+        // a row left without variables must have a zero right-hand side
+        let consistent = !rows[pivots.len()..].iter().any(|row| bit(row, n));
+        rows.truncate(pivots.len());
+
+        let free: Vec<usize> = (0..n).filter(|col| !pivots.contains(col)).collect();
+        if free.len() >= WORD {
+            return Err(TooManyFree(free.len()));
+        }
+        let masks = if consistent { 1 << free.len() } else { 0 };
+        Ok(Solutions { variables: n, rows, pivots, free, next: 0, masks })
+    }
+}
+
+/// More free variables than [`System::solutions`] can enumerate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TooManyFree(pub usize);
+
+impl fmt::Display for TooManyFree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} free variables are too many to enumerate", self.0)
+    }
+}
+
+impl std::error::Error for TooManyFree {}
+
+/// The solutions of a [`System`], one per assignment of its free variables.
+#[derive(Clone, Debug)]
+pub struct Solutions {
+    variables: usize,
+    /// The reduced rows, one per pivot.
+    rows: Vec<Vec<u64>>,
+    pivots: Vec<usize>,
+    free: Vec<usize>,
+    /// The assignment of the free variables to produce next, one bit each.
+    next: u64,
+    masks: u64,
+}
+
+impl Iterator for Solutions {
+    type Item = Vec<bool>;
+
+    fn next(&mut self) -> Option<Vec<bool>> {
+        if self.next == self.masks {
+            return None;
+        }
+        let (mask, n) = (self.next, self.variables);
+        self.next += 1;
+        let mut x = vec![false; n];
+        for (k, &f) in self.free.iter().enumerate() {
+            x[f] = mask >> k & 1 == 1;
+        }
+        for (row, &col) in self.rows.iter().zip(&self.pivots) {
+            x[col] = self.free.iter().fold(bit(row, n), |v, &f| v ^ (bit(row, f) && x[f]));
+        }
+        Some(x)
+    }
+}
+
+fn bit(row: &[u64], i: usize) -> bool {
+    row[i / WORD] >> (i % WORD) & 1 == 1
+}
+
+fn flip(row: &mut [u64], i: usize) {
+    row[i / WORD] ^= 1 << (i % WORD);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_lightest_solution() {
+        // the first example machine: lights .##. and buttons (3) (1,3) (2) (2,3) (0,2) (0,1)
+        let buttons: [&[usize]; 6] = [&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]];
+        let mut system = System::new(buttons.len());
+        for (light, on) in [false, true, true, false].into_iter().enumerate() {
+            system.equation((0..buttons.len()).filter(|&b| buttons[b].contains(&light)), on);
+        }
+        let x = system.min_weight_solution().unwrap().unwrap();
+        assert_eq!(x.iter().filter(|&&on| on).count(), 2);
+        // six buttons, four independent lights: two free variables
+        assert_eq!(system.solutions().unwrap().count(), 4);
+        for (light, on) in [false, true, true, false].into_iter().enumerate() {
+            let toggles = (0..buttons.len()).filter(|&b| x[b] && buttons[b].contains(&light)).count();
+            assert_eq!(toggles % 2 == 1, on);
+        }
+    }
+
+    #[test]
+    fn detects_contradictions() {
+        let mut system = System::new(2);
+        system.equation([0, 1], true);
+        system.equation([0], false);
+        system.equation([1], false);
+        assert_eq!(system.min_weight_solution(), Ok(None));
+        assert_eq!(system.solutions().unwrap().next(), None);
+
+        // repeated terms cancel, also across words, and no equations leave everything off
+        let mut system = System::new(70);
+        for v in 0..69 {
+            system.equation([v, v + 1], false);
+        }
+        system.equation([69, 3, 3], true);
+        assert_eq!(system.min_weight_solution(), Ok(Some(vec![true; 70])));
+        assert_eq!(System::new(3).min_weight_solution(), Ok(Some(vec![false; 3])));
+
+        // every variable free
+        assert_eq!(System::new(70).min_weight_solution(), Err(TooManyFree(70)));
+    }
+}
@@ -0,0 +1,373 @@
+//! Directed graphs with named nodes.
+
+use crate::answer::Number;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+/// A directed graph whose nodes are interned names, addressed by dense `usize` ids in the order
+/// they were first seen.
+#[derive(Clone, Debug, Default)]
+pub struct Graph {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    edges: Vec<Vec<usize>>,
+}
+
+/// A cycle found where the graph has to be acyclic, e.g. because infinitely many paths run through it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// The nodes along the cycle, in edge order; the last one links back to the first.
+    pub nodes: Vec<String>,
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "graph has a cycle: ")?;
+        for node in &self.nodes {
+            write!(f, "{node} -> ")?;
+        }
+        write!(f, "{}", self.nodes.first().map_or("", String::as_str))
+    }
+}
+
+impl std::error::Error for Cycle {}
+
+/// Why [`Graph::count_paths_via`] could not count the paths.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathError {
+    /// A cycle on some path between the endpoints, which makes the count infinite.
+    Cycle(Cycle),
+    /// Too many waypoints for the number of nodes on the paths: the table would have more than
+    /// [`MAX_TABLE_CELLS`] cells.
+    TableTooLarge { nodes: usize, waypoints: usize },
+}
+
+/// The most cells [`Graph::count_paths_via`] allocates, one per node on the paths and set of waypoints seen.
+pub const MAX_TABLE_CELLS: usize = 1 << 22;
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::Cycle(cycle) => cycle.fmt(f),
+            PathError::TableTooLarge { nodes, waypoints } => write!(
+                f,
+                "{waypoints} waypoints are too many for {nodes} nodes; the table of {nodes} × 2^{waypoints} cells exceeds {MAX_TABLE_CELLS}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+impl From<Cycle> for PathError {
+    fn from(cycle: Cycle) -> PathError {
+        PathError::Cycle(cycle)
+    }
+}
+
+impl Graph {
+    pub fn new() -> Graph {
+        Graph::default()
+    }
+
+    /// The id of `name`, adding it as a new node if it is not in the graph yet.
+    pub fn node(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.edges.push(vec![]);
+        id
+    }
+
+    /// The id of `name`, if it is in the graph.
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: usize) -> &str {
+        &self.names[id]
+    }
+
+    /// Number of nodes.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize) {
+        self.edges[from].push(to);
+    }
+
+    pub fn neighbors(&self, id: usize) -> &[usize] {
+        &self.edges[id]
+    }
+
+    /// The same graph with every edge turned around.
+    pub fn reversed(&self) -> Graph {
+        let mut edges = vec![vec![]; self.len()];
+        for (from, targets) in self.edges.iter().enumerate() {
+            for &to in targets {
+                edges[to].push(from);
+            }
+        }
+        Graph { names: self.names.clone(), ids: self.ids.clone(), edges }
+    }
+
+    /// Which nodes can be reached from `from`, `from` itself included.
+    pub fn reachable(&self, from: usize) -> Vec<bool> {
+        let mut seen = vec![false; self.len()];
+        seen[from] = true;
+        let mut stack = vec![from];
+        while let Some(node) = stack.pop() {
+            for &next in &self.edges[node] {
+                if !std::mem::replace(&mut seen[next], true) {
+                    stack.push(next);
+                }
+            }
+        }
+        seen
+    }
+
+    /// A fewest-edges path from `from` to `to`, both included, or `None` if `to` is unreachable.
+    pub fn shortest_path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        let mut previous: Vec<Option<usize>> = vec![None; self.len()];
+        let mut queue = VecDeque::from([from]);
+        let mut seen = vec![false; self.len()];
+        seen[from] = true;
+        while let Some(node) = queue.pop_front() {
+            if node == to {
+                let mut path = vec![to];
+                while let Some(p) = previous[*path.last()?] {
+                    path.push(p);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for &next in &self.edges[node] {
+                if !std::mem::replace(&mut seen[next], true) {
+                    previous[next] = Some(node);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Every node ordered so that edges only point forwards.
+    pub fn topological_order(&self) -> Result<Vec<usize>, Cycle> {
+        self.topological_order_within(&vec![true; self.len()])
+    }
+
+    /// Some cycle of the graph, if it has one.
+    pub fn find_cycle(&self) -> Option<Cycle> {
+        self.topological_order().err()
+    }
+
+    /// Kahn's algorithm on the subgraph of the nodes marked in `keep`.
+    fn topological_order_within(&self, keep: &[bool]) -> Result<Vec<usize>, Cycle> {
+        let mut indegree = vec![0usize; self.len()];
+        for (from, targets) in self.edges.iter().enumerate() {
+            if keep[from] {
+                for &to in targets.iter().filter(|&&to| keep[to]) {
+                    indegree[to] += 1;
+                }
+            }
+        }
+        let mut ready: Vec<usize> = (0..self.len()).filter(|&n| keep[n] && indegree[n] == 0).collect();
+        let mut order = Vec::with_capacity(ready.len());
+        while let Some(node) = ready.pop() {
+            order.push(node);
+            for &next in self.edges[node].iter().filter(|&&to| keep[to]) {
+                indegree[next] -= 1;
+                if indegree[next] == 0 {
+                    ready.push(next);
+                }
+            }
+        }
+        if order.len() == keep.iter().filter(|&&k| k).count() {
+            return Ok(order);
+        }
+        // every node left over still has a left-over predecessor, so walking backwards must loop
+        let stuck: Vec<bool> = (0..self.len()).map(|n| keep[n] && indegree[n] > 0).collect();
+        let reversed = self.reversed();
+        let mut walk = vec![(0..self.len()).find(|&n| stuck[n]).unwrap_or(0)];
+        let mut position = HashMap::from([(walk[0], 0)]);
+        loop {
+            let node = *walk.last().unwrap_or(&0);
+            let Some(&previous) = reversed.edges[node].iter().find(|&&p| stuck[p]) else {
+                unreachable!("a stuck node always has a stuck predecessor");
+            };
+            if let Some(&start) = position.get(&previous) {
+                let mut cycle: Vec<usize> = walk.split_off(start);
+                cycle.reverse();
+                // start at the earliest node so the same cycle is always reported the same way
+                let first = cycle.iter().enumerate().min_by_key(|&(_, &n)| n).map_or(0, |(i, _)| i);
+                cycle.rotate_left(first);
+                return Err(Cycle { nodes: cycle.into_iter().map(|n| self.names[n].clone()).collect() });
+            }
+            position.insert(previous, walk.len());
+            walk.push(previous);
+        }
+    }
+
+    /// Number of distinct paths from `from` to `to`. Cycles elsewhere in the graph are fine, but a
+    /// cycle on some path from `from` to `to` would make the count infinite and is an error.
+    pub fn count_paths(&self, from: usize, to: usize) -> Result<Number, PathError> {
+        self.count_paths_via(from, to, &[])
+    }
+
+    /// Number of distinct paths from `from` to `to` that visit every node in `via`, in any order.
+    ///
+    /// A dynamic program over the topological order that tracks which waypoints each partial path
+    /// has seen as a bitmask, so it costs `2^via.len()` times a plain count over the nodes that lie
+    /// on some path; fine for a handful, and a table over [`MAX_TABLE_CELLS`] is an error. Counts grow
+    /// exponentially with the depth of the graph and widen to big integers as needed.
+    pub fn count_paths_via(&self, from: usize, to: usize, via: &[usize]) -> Result<Number, PathError> {
+        let mut waypoints = via.to_vec();
+        waypoints.sort_unstable();
+        waypoints.dedup();
+
+        let forward = self.reachable(from);
+        let backward = self.reversed().reachable(to);
+        let relevant: Vec<bool> = forward.iter().zip(&backward).map(|(&f, &b)| f && b).collect();
+        let order = self.topological_order_within(&relevant)?;
+        // no path at all, or none that can pass some waypoint
+        if !relevant[from] || waypoints.iter().any(|&w| !relevant[w]) {
+            return Ok(Number::ZERO);
+        }
+
+        let too_large = PathError::TableTooLarge { nodes: order.len(), waypoints: waypoints.len() };
+        let masks = u32::try_from(waypoints.len()).ok().and_then(|n| 1usize.checked_shl(n)).ok_or(too_large.clone())?;
+        if order.len().checked_mul(masks).is_none_or(|cells| cells > MAX_TABLE_CELLS) {
+            return Err(too_large);
+        }
+        let bit = |node: usize| waypoints.binary_search(&node).map_or(0, |i| 1usize << i);
+        // the row of each relevant node in the table, by its place in the order
+        let mut row = vec![usize::MAX; self.len()];
+        for (i, &node) in order.iter().enumerate() {
+            row[node] = i;
+        }
+        // paths[row[node] * masks + mask]: partial paths ending at `node` having visited exactly `mask`
+        let mut paths = vec![Number::ZERO; order.len() * masks];
+        paths[row[from] * masks + bit(from)] = Number::ONE;
+        for &node in &order {
+            for &next in self.edges[node].iter().filter(|&&n| relevant[n]) {
+                for mask in 0..masks {
+                    let count = &paths[row[node] * masks + mask];
+                    if !count.is_zero() {
+                        let count = count.clone();
+                        paths[row[next] * masks + (mask | bit(next))] += count;
+                    }
+                }
+            }
+        }
+        Ok(paths.swap_remove(row[to] * masks + masks - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(lines: &str) -> Graph {
+        let mut graph = Graph::new();
+        for line in lines.lines() {
+            let (from, targets) = line.split_once(':').unwrap();
+            let from = graph.node(from.trim());
+            for to in targets.split_whitespace() {
+                let to = graph.node(to);
+                graph.add_edge(from, to);
+            }
+        }
+        graph
+    }
+
+    const EXAMPLE: &str = "aaa: you hhh\nyou: bbb ccc\nbbb: ddd eee\nccc: ddd eee fff\nddd: ggg\n\
+                           eee: out\nfff: out\nggg: out\nhhh: ccc fff iii\niii: out";
+
+    #[test]
+    fn interns_names() {
+        let mut g = graph(EXAMPLE);
+        assert_eq!(g.len(), 11);
+        assert_eq!(g.id("you"), Some(1));
+        assert_eq!(g.name(1), "you");
+        assert_eq!(g.node("you"), 1);
+        assert_eq!(g.id("nobody"), None);
+    }
+
+    #[test]
+    fn counts_paths() {
+        let g = graph(EXAMPLE);
+        let (you, out) = (g.id("you").unwrap(), g.id("out").unwrap());
+        assert_eq!(g.count_paths(you, out), Ok(Number::from(5)));
+        assert_eq!(g.count_paths(out, you), Ok(Number::from(0)));
+        assert_eq!(g.count_paths(you, you), Ok(Number::from(1)));
+    }
+
+    #[test]
+    fn orders_topologically() {
+        let g = graph(EXAMPLE);
+        let order = g.topological_order().unwrap();
+        let mut position = vec![0; g.len()];
+        for (i, &n) in order.iter().enumerate() {
+            position[n] = i;
+        }
+        assert!((0..g.len()).all(|n| g.neighbors(n).iter().all(|&m| position[n] < position[m])));
+        assert_eq!(g.find_cycle(), None);
+    }
+
+    #[test]
+    fn reachability_and_shortest_paths() {
+        let g = graph(EXAMPLE);
+        let id = |name| g.id(name).unwrap();
+        let from_hhh = g.reachable(id("hhh"));
+        assert!(from_hhh[id("out")] && from_hhh[id("ddd")] && !from_hhh[id("you")]);
+        let path: Vec<&str> = g.shortest_path(id("aaa"), id("out")).unwrap().into_iter().map(|n| g.name(n)).collect();
+        assert_eq!(path, ["aaa", "hhh", "fff", "out"]);
+        assert_eq!(g.shortest_path(id("out"), id("aaa")), None);
+    }
+
+    #[test]
+    fn names_cycles() {
+        let g = graph("a: b\nb: c\nc: d b\nd: out\nx: y\ny: x");
+        let cycle = g.find_cycle().unwrap();
+        assert_eq!(cycle.nodes.len(), 2);
+        let (a, out) = (g.id("a").unwrap(), g.id("out").unwrap());
+        let err = g.count_paths(a, out).unwrap_err();
+        assert_eq!(err.to_string(), "graph has a cycle: b -> c -> b");
+        // the x-y cycle is not on any path from a to out
+        let g = graph("a: out\nx: y\ny: x");
+        assert_eq!(g.count_paths(0, 1), Ok(Number::from(1)));
+    }
+
+    #[test]
+    fn counts_paths_through_waypoints() {
+        let g = graph("svr: aaa bbb\naaa: fft\nfft: ccc\nbbb: tty\ntty: ccc\nccc: ddd eee\nddd: hub\nhub: fff\n\
+                       eee: dac\ndac: fff\nfff: ggg hhh\nggg: out\nhhh: out");
+        let id = |name| g.id(name).unwrap();
+        let (svr, out) = (id("svr"), id("out"));
+        assert_eq!(g.count_paths_via(svr, out, &[]), Ok(Number::from(8)));
+        assert_eq!(g.count_paths_via(svr, out, &[id("fft"), id("dac")]), Ok(Number::from(2)));
+        assert_eq!(g.count_paths_via(svr, out, &[id("dac"), id("fft"), id("dac")]), Ok(Number::from(2)));
+        assert_eq!(g.count_paths_via(svr, out, &[id("fft"), id("dac"), id("hhh")]), Ok(Number::from(1)));
+        assert_eq!(g.count_paths_via(svr, out, &[id("hub"), id("dac")]), Ok(Number::from(0)));
+        // the endpoints count as visited
+        assert_eq!(g.count_paths_via(svr, out, &[svr, out]), Ok(Number::from(8)));
+
+        // every node of a long chain as a waypoint: nodes × 2^waypoints cells is too many
+        let chain: Vec<String> = (0..22).map(|i| format!("n{i}: n{}", i + 1)).collect();
+        let g = graph(&chain.join("\n"));
+        let nodes: Vec<usize> = (0..=22).map(|i| g.id(&format!("n{i}")).unwrap()).collect();
+        assert_eq!(g.count_paths_via(nodes[0], nodes[22], &nodes[..16]), Ok(Number::from(1)));
+        assert_eq!(
+            g.count_paths_via(nodes[0], nodes[22], &nodes),
+            Err(PathError::TableTooLarge { nodes: 23, waypoints: 23 })
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! A rectangular grid stored row by row, for the puzzles that come as character maps.
+
+use crate::ParseError;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// Offsets of the four orthogonal neighbours, clockwise from north.
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets of all eight surrounding cells, clockwise from north-west.
+const SURROUNDING: [(isize, isize); 8] = [(-1, -1), (0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0)];
+
+/// Cells are addressed as `(x, y)`: `x` is the column, `y` the row, `(0, 0)` the top-left corner.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A `width` x `height` grid with every cell set to `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid { width, height, cells: vec![fill; width * height] }
+    }
+
+    /// Parse a character map, one row per line. `cell` turns a character into a value and rejects
+    /// it with `None`; `text` must be a slice of `input` so errors can point at the offending cell.
+    /// Trailing blank lines are ignored; every other row must be as wide as the first.
+    pub fn parse(
+        input: &str,
+        text: &str,
+        expected: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Grid<T>, ParseError> {
+        let text = text.trim_end_matches(['\n', '\r']);
+        let mut width = None;
+        let mut cells = vec![];
+        let mut height = 0;
+        for line in text.lines() {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            let row_width = line.chars().count();
+            if *width.get_or_insert(row_width) != row_width {
+                return Err(ParseError::at(input, line, format!("a row of {} cells", width.unwrap_or(0))));
+            }
+            for (i, c) in line.char_indices() {
+                cells.push(cell(c).ok_or_else(|| ParseError::at(input, &line[i..i + c.len_utf8()], expected))?);
+            }
+            height += 1;
+        }
+        Ok(Grid { width: width.unwrap_or(0), height, cells })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        (x < self.width && y < self.height).then(|| &mut self.cells[y * self.width + x])
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.width * self.height).map(move |i| (i % width, i / width))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The up to four orthogonal neighbours of `(x, y)` that lie inside the grid.
+    pub fn neighbors4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.offsets(x, y, &ORTHOGONAL)
+    }
+
+    /// The up to eight cells surrounding `(x, y)` that lie inside the grid, diagonals included.
+    pub fn neighbors8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.offsets(x, y, &SURROUNDING)
+    }
+
+    fn offsets(
+        &self,
+        x: usize,
+        y: usize,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.width, self.height);
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let nx = x.checked_add_signed(dx).filter(|&nx| nx < width)?;
+            let ny = y.checked_add_signed(dy).filter(|&ny| ny < height)?;
+            Some((nx, ny))
+        })
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(x < self.width, "column {x} out of bounds for width {}", self.width);
+        self.cells[x..].iter().step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Apply `f` to every cell, keeping the shape.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+
+    /// Swap rows and columns, so `(x, y)` becomes `(y, x)`.
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let cells = self.columns().flat_map(|column| column.cloned()).collect();
+        Grid { width: self.height, height: self.width, cells }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y)
+            .unwrap_or_else(|| panic!("({x}, {y}) out of bounds for a {}x{} grid", self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(x, y)
+            .unwrap_or_else(|| panic!("({x}, {y}) out of bounds for a {width}x{height} grid"))
+    }
+}
+
+/// One line per row with the cells written back to back, so a `Grid<char>` prints as its map.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(text: &str) -> Grid<char> {
+        Grid::parse(text, text, "a cell", Some).unwrap()
+    }
+
+    #[test]
+    fn parses_and_prints_maps() {
+        let grid = map("#..\n.#.\r\n\n");
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 1)], '#');
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.to_string(), "#..\n.#.\n");
+        assert_eq!(map("").width(), 0);
+    }
+
+    #[test]
+    fn reports_bad_cells_and_ragged_rows() {
+        let input = "#.\n.x\n";
+        let err = Grid::parse(input, input, "`#` or `.`", |c| (c != 'x').then_some(c)).unwrap_err();
+        assert_eq!((err.line, err.column, err.found.as_str()), (2, 2, "x"));
+
+        let input = "##\n#\n";
+        let err = Grid::parse(input, input, "a cell", Some).unwrap_err();
+        assert_eq!((err.line, err.expected.as_str()), (2, "a row of 2 cells"));
+    }
+
+    #[test]
+    fn neighbours_stay_inside() {
+        let grid = Grid::new(3, 2, 0);
+        assert_eq!(grid.neighbors4(0, 0).collect::<Vec<_>>(), [(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbors4(1, 1).count(), 3);
+        assert_eq!(grid.neighbors8(0, 0).collect::<Vec<_>>(), [(1, 0), (1, 1), (0, 1)]);
+        assert_eq!(grid.neighbors8(1, 0).count(), 5);
+    }
+
+    #[test]
+    fn rows_columns_and_transpose() {
+        let grid = map("abc\ndef\n");
+        assert_eq!(grid.row(1), ['d', 'e', 'f']);
+        assert_eq!(grid.column(2).collect::<String>(), "cf");
+        assert_eq!(grid.columns().count(), 3);
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.transpose()[(1, 2)], grid[(2, 1)]);
+        assert_eq!(grid.map(|c| c.is_ascii_uppercase()).iter().filter(|(_, &up)| up).count(), 0);
+        assert_eq!(grid.positions().nth(4), Some((1, 1)));
+    }
+}
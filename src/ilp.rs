@@ -0,0 +1,328 @@
+//! Integer linear programs, built the same way whether or not a solver is compiled in.
+//!
+//! Models can always be written out as CPLEX LP or MPS files, to inspect them or solve them with
+//! other tools. Solving needs the `ilp` feature, which comes with picking a backend: `cbc` (native CBC),
+//! `highs` (native HiGHS) or `microlp` (pure Rust). Without one, [`Model::solve`] returns an
+//! error and the days use their own exact solvers instead.
+
+use anyhow::Result;
+use std::fmt::Write as _;
+#[cfg(feature = "cbc")]
+use good_lp::coin_cbc as backend;
+#[cfg(all(feature = "highs", not(feature = "cbc")))]
+use good_lp::highs as backend;
+#[cfg(all(feature = "microlp", not(any(feature = "cbc", feature = "highs"))))]
+use good_lp::microlp as backend;
+
+#[cfg(all(feature = "ilp", not(any(feature = "cbc", feature = "highs", feature = "microlp"))))]
+compile_error!("the `ilp` feature needs a backend: enable `cbc`, `highs` or `microlp`");
+
+/// The backend [`Model::solve`] uses, if any. With several enabled the first of `cbc`, `highs`,
+/// `microlp` wins.
+pub const BACKEND: Option<&str> = if cfg!(feature = "cbc") {
+    Some("cbc")
+} else if cfg!(feature = "highs") {
+    Some("highs")
+} else if cfg!(feature = "microlp") {
+    Some("microlp")
+} else {
+    None
+};
+
+/// How many terms or names [`Model::to_lp`] puts on one line.
+const LINE_TERMS: usize = 10;
+
+/// An integer variable with inclusive bounds; `max: None` is unbounded above.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Variable {
+    pub name: String,
+    pub min: i64,
+    pub max: Option<i64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Relation {
+    Equal,
+    AtMost,
+}
+
+/// `sum(coefficient * variable) <relation> rhs`, with variables given by index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Constraint {
+    pub name: String,
+    pub terms: Vec<(usize, i64)>,
+    pub relation: Relation,
+    pub rhs: i64,
+}
+
+/// Minimise a linear objective over integer variables subject to linear constraints.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Model {
+    variables: Vec<Variable>,
+    constraints: Vec<Constraint>,
+    objective: Vec<(usize, i64)>,
+}
+
+impl Model {
+    pub fn new() -> Model {
+        Model::default()
+    }
+
+    /// Add a variable and return its index.
+    pub fn variable(&mut self, name: impl Into<String>, min: i64, max: Option<i64>) -> usize {
+        self.variables.push(Variable { name: name.into(), min, max });
+        self.variables.len() - 1
+    }
+
+    pub fn constraint(
+        &mut self,
+        name: impl Into<String>,
+        terms: impl IntoIterator<Item = (usize, i64)>,
+        relation: Relation,
+        rhs: i64,
+    ) {
+        let terms: Vec<(usize, i64)> = terms.into_iter().collect();
+        assert!(terms.iter().all(|&(v, _)| v < self.variables.len()), "constraint on an unknown variable");
+        self.constraints.push(Constraint { name: name.into(), terms, relation, rhs });
+    }
+
+    /// Set the objective to minimise; a model without one only asks for a feasible point.
+    pub fn minimise(&mut self, terms: impl IntoIterator<Item = (usize, i64)>) {
+        self.objective = terms.into_iter().collect();
+    }
+
+    pub fn variables(&self) -> &[Variable] {
+        &self.variables
+    }
+
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+
+    pub fn objective(&self) -> &[(usize, i64)] {
+        &self.objective
+    }
+
+    /// The model in CPLEX LP format. Every variable is listed under `General`, with its bounds.
+    pub fn to_lp(&self, name: &str) -> String {
+        let mut lp = format!("\\ {name}\nMinimize\n obj:{}\nSubject To\n", self.lp_terms(&self.objective));
+        for c in &self.constraints {
+            let relation = match c.relation {
+                Relation::Equal => "=",
+                Relation::AtMost => "<=",
+            };
+            writeln!(lp, " {}:{} {relation} {}", c.name, self.lp_terms(&c.terms), c.rhs).unwrap();
+        }
+        lp.push_str("Bounds\n");
+        for v in &self.variables {
+            match v.max {
+                Some(max) => writeln!(lp, " {} <= {} <= {max}", v.min, v.name).unwrap(),
+                None => writeln!(lp, " {} >= {}", v.name, v.min).unwrap(),
+            }
+        }
+        lp.push_str("General\n");
+        for names in self.variables.chunks(LINE_TERMS) {
+            let names: Vec<&str> = names.iter().map(|v| v.name.as_str()).collect();
+            writeln!(lp, " {}", names.join(" ")).unwrap();
+        }
+        lp.push_str("End\n");
+        lp
+    }
+
+    /// `terms` as an LP expression with a leading space, wrapped so no line gets too long for
+    /// readers with a line limit. An empty sum is written as zero times the first variable, since
+    /// LP has no constant-only expressions.
+    fn lp_terms(&self, terms: &[(usize, i64)]) -> String {
+        let mut text = String::new();
+        if terms.is_empty() {
+            if let Some(first) = self.variables.first() {
+                write!(text, " 0 {}", first.name).unwrap();
+            }
+            return text;
+        }
+        for (i, &(v, coefficient)) in terms.iter().enumerate() {
+            if i > 0 && i % LINE_TERMS == 0 {
+                text.push_str("\n   ");
+            }
+            let sign = match (i, coefficient < 0) {
+                (_, true) => " -",
+                (0, false) => "",
+                _ => " +",
+            };
+            let size = match coefficient.unsigned_abs() {
+                1 => String::new(),
+                size => format!("{size} "),
+            };
+            write!(text, "{sign} {size}{}", self.variables[v].name).unwrap();
+        }
+        text
+    }
+
+    /// The model in free MPS format, minimising the row `obj`. Every variable sits between
+    /// integer markers, and its bounds are written out, since readers disagree on the default
+    /// upper bound of an integer column.
+    pub fn to_mps(&self, name: &str) -> String {
+        let mut mps = format!("NAME {name}\nROWS\n N obj\n");
+        for c in &self.constraints {
+            let kind = match c.relation {
+                Relation::Equal => "E",
+                Relation::AtMost => "L",
+            };
+            writeln!(mps, " {kind} {}", c.name).unwrap();
+        }
+
+        let mut columns: Vec<Vec<(&str, i64)>> = vec![vec![]; self.variables.len()];
+        for &(v, coefficient) in &self.objective {
+            columns[v].push(("obj", coefficient));
+        }
+        for c in &self.constraints {
+            for &(v, coefficient) in &c.terms {
+                columns[v].push((&c.name, coefficient));
+            }
+        }
+        mps.push_str("COLUMNS\n MARKER 'MARKER' 'INTORG'\n");
+        for (v, entries) in self.variables.iter().zip(&columns) {
+            if entries.is_empty() {
+                // a column must appear to exist
+                writeln!(mps, " {} obj 0", v.name).unwrap();
+            }
+            for (row, coefficient) in entries {
+                writeln!(mps, " {} {row} {coefficient}", v.name).unwrap();
+            }
+        }
+        mps.push_str(" MARKER 'MARKER' 'INTEND'\nRHS\n");
+        for c in self.constraints.iter().filter(|c| c.rhs != 0) {
+            writeln!(mps, " RHS {} {}", c.name, c.rhs).unwrap();
+        }
+        mps.push_str("BOUNDS\n");
+        for v in &self.variables {
+            writeln!(mps, " LI BND {} {}", v.name, v.min).unwrap();
+            match v.max {
+                Some(max) => writeln!(mps, " UI BND {} {max}", v.name).unwrap(),
+                None => writeln!(mps, " PL BND {}", v.name).unwrap(),
+            }
+        }
+        mps.push_str("ENDATA\n");
+        mps
+    }
+
+    /// An optimal value for every variable, `None` if the model is infeasible.
+    #[cfg(feature = "ilp")]
+    pub fn solve(&self) -> Result<Option<Vec<i64>>> {
+        use good_lp::{variable, Expression, ProblemVariables, ResolutionError, Solution, SolverModel};
+
+        let mut vars = ProblemVariables::new();
+        let xs: Vec<good_lp::Variable> = self
+            .variables
+            .iter()
+            .map(|v| {
+                let definition = variable().integer().min(v.min as f64);
+                vars.add(match v.max {
+                    Some(max) => definition.max(max as f64),
+                    None => definition,
+                })
+            })
+            .collect();
+        let expression = |terms: &[(usize, i64)]| terms.iter().map(|&(v, c)| xs[v] * c as f64).sum::<Expression>();
+
+        let mut problem = vars.minimise(expression(&self.objective)).using(backend);
+        for c in &self.constraints {
+            let lhs = expression(&c.terms);
+            problem.add_constraint(match c.relation {
+                Relation::Equal => lhs.eq(c.rhs as f64),
+                Relation::AtMost => lhs.leq(c.rhs as f64),
+            });
+        }
+        match problem.solve() {
+            Ok(solution) => Ok(Some(xs.iter().map(|&x| solution.value(x).round() as i64).collect())),
+            Err(ResolutionError::Infeasible) => Ok(None),
+            Err(e) => Err(anyhow::anyhow!("{} failed: {e}", BACKEND.unwrap_or("ILP backend"))),
+        }
+    }
+
+    /// Always an error: this build has no ILP backend.
+    #[cfg(not(feature = "ilp"))]
+    pub fn solve(&self) -> Result<Option<Vec<i64>>> {
+        anyhow::bail!("built without an ILP backend; rebuild with `--features microlp` (or `cbc`, `highs`)")
+    }
+}
+
+/// A model to export under `name`, built only when asked for: a day may describe hundreds of large
+/// models, which should not all be held at once.
+pub struct Pending<'a> {
+    pub name: String,
+    build: Box<dyn FnOnce() -> Model + 'a>,
+}
+
+impl<'a> Pending<'a> {
+    pub fn new(name: impl Into<String>, build: impl FnOnce() -> Model + 'a) -> Pending<'a> {
+        Pending { name: name.into(), build: Box::new(build) }
+    }
+
+    pub fn build(self) -> Model {
+        (self.build)()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two presses of (0,1) and one of (1) reach {2,3}; (0) alone would need more presses.
+    fn presses() -> Model {
+        let mut model = Model::new();
+        let buttons = [[0].as_slice(), &[1], &[0, 1]].map(|wired| wired.to_vec());
+        let xs: Vec<usize> = (0..buttons.len()).map(|b| model.variable(format!("b{b}"), 0, None)).collect();
+        for (counter, target) in [2, 3].into_iter().enumerate() {
+            let wired = xs.iter().filter(|&&b| buttons[b].contains(&counter)).map(|&b| (b, 1));
+            model.constraint(format!("c{counter}"), wired, Relation::Equal, target);
+        }
+        model.minimise(xs.iter().map(|&x| (x, 1)));
+        model
+    }
+
+    #[test]
+    fn builds_models() {
+        let model = presses();
+        assert_eq!(model.variables().len(), 3);
+        assert_eq!(model.constraints()[1].terms, [(1, 1), (2, 1)]);
+        assert_eq!(model.objective().len(), 3);
+    }
+
+    #[test]
+    fn writes_lp_and_mps() {
+        let mut model = presses();
+        model.constraint("spare", [(0, 2), (1, -1)], Relation::AtMost, 0);
+        assert_eq!(
+            model.to_lp("presses"),
+            "\\ presses\nMinimize\n obj: b0 + b1 + b2\nSubject To\n c0: b0 + b2 = 2\n c1: b1 + b2 = 3\n spare: 2 b0 - b1 <= 0\n\
+             Bounds\n b0 >= 0\n b1 >= 0\n b2 >= 0\nGeneral\n b0 b1 b2\nEnd\n"
+        );
+        let mps = model.to_mps("presses");
+        assert!(mps.starts_with("NAME presses\nROWS\n N obj\n E c0\n E c1\n L spare\nCOLUMNS\n MARKER 'MARKER' 'INTORG'\n b0 obj 1\n b0 c0 1\n b0 spare 2\n"));
+        assert!(mps.ends_with("RHS\n RHS c0 2\n RHS c1 3\nBOUNDS\n LI BND b0 0\n PL BND b0\n LI BND b1 0\n PL BND b1\n LI BND b2 0\n PL BND b2\nENDATA\n"));
+
+        // an objective without terms is still a valid LP expression
+        let mut feasible = Model::new();
+        feasible.variable("x", 0, Some(1));
+        assert!(feasible.to_lp("f").contains(" obj: 0 x\n"));
+        assert!(feasible.to_lp("f").contains(" 0 <= x <= 1\n"));
+    }
+
+    #[cfg(feature = "ilp")]
+    #[test]
+    fn solves_and_detects_infeasibility() {
+        assert_eq!(presses().solve().unwrap(), Some(vec![0, 1, 2]));
+        let mut model = Model::new();
+        let x = model.variable("x", 0, Some(1));
+        model.constraint("too_much", [(x, 1)], Relation::Equal, 2);
+        assert_eq!(model.solve().unwrap(), None);
+    }
+
+    #[cfg(not(feature = "ilp"))]
+    #[test]
+    fn solving_needs_a_backend() {
+        assert!(presses().solve().is_err());
+        assert_eq!(BACKEND, None);
+    }
+}
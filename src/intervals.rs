@@ -0,0 +1,167 @@
+//! Sets of integers stored as sorted, disjoint inclusive ranges.
+
+/// A set of `i64`s kept as sorted inclusive `(start, end)` ranges that neither overlap nor touch,
+/// so `3-5` and `6-8` are stored as the single range `3-8`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    ranges: Vec<(i64, i64)>,
+}
+
+impl IntervalSet {
+    pub fn new() -> IntervalSet {
+        IntervalSet::default()
+    }
+
+    /// Add every integer in `start..=end`, merging with the ranges it overlaps or touches.
+    /// An empty range (`start > end`) is ignored.
+    pub fn insert(&mut self, start: i64, end: i64) {
+        if start > end {
+            return;
+        }
+        // ranges before `first` end too early to touch the new one, ranges from `last` on start too late
+        let first = self.ranges.partition_point(|&(_, e)| e < start.saturating_sub(1));
+        let last = self.ranges.partition_point(|&(s, _)| s <= end.saturating_add(1));
+        let merged = match self.ranges[first..last] {
+            [] => (start, end),
+            ref touching => (start.min(touching[0].0), end.max(touching[touching.len() - 1].1)),
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    /// Add every range of `other`.
+    pub fn merge(&mut self, other: &IntervalSet) {
+        for &(start, end) in &other.ranges {
+            self.insert(start, end);
+        }
+    }
+
+    /// Binary search over the ranges.
+    pub fn contains(&self, x: i64) -> bool {
+        let i = self.ranges.partition_point(|&(_, end)| end < x);
+        self.ranges.get(i).is_some_and(|&(start, _)| start <= x)
+    }
+
+    /// The disjoint ranges, in increasing order.
+    pub fn ranges(&self) -> &[(i64, i64)] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of integers in the set; a `u128`, since the whole `i64` range holds 2^64 of them.
+    pub fn len(&self) -> u128 {
+        self.ranges.iter().map(|&(start, end)| u128::from(end.abs_diff(start)) + 1).sum()
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut out = self.clone();
+        out.merge(other);
+        out
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let (mut i, mut j) = (0, 0);
+        let mut ranges = vec![];
+        while let (Some(&(a0, a1)), Some(&(b0, b1))) = (self.ranges.get(i), other.ranges.get(j)) {
+            let (start, end) = (a0.max(b0), a1.min(b1));
+            if start <= end {
+                ranges.push((start, end));
+            }
+            // drop whichever range ends first; it cannot meet anything further along
+            if a1 < b1 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    /// The integers in `self` but not in `other`.
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges = vec![];
+        let mut j = 0;
+        'ranges: for &(start, end) in &self.ranges {
+            let mut start = start;
+            // skip the ranges of `other` that end before this one starts
+            while other.ranges.get(j).is_some_and(|&(_, e)| e < start) {
+                j += 1;
+            }
+            let mut k = j;
+            while let Some(&(cut_start, cut_end)) = other.ranges.get(k).filter(|&&(s, _)| s <= end) {
+                if cut_start > start {
+                    ranges.push((start, cut_start - 1));
+                }
+                // nothing is left of this range once a cut reaches its end (or `i64::MAX`)
+                match cut_end.checked_add(1).filter(|&next| next <= end) {
+                    Some(next) => start = next,
+                    None => continue 'ranges,
+                }
+                k += 1;
+            }
+            ranges.push((start, end));
+        }
+        IntervalSet { ranges }
+    }
+}
+
+impl FromIterator<(i64, i64)> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = (i64, i64)>>(iter: I) -> IntervalSet {
+        let mut set = IntervalSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<(i64, i64)> for IntervalSet {
+    fn extend<I: IntoIterator<Item = (i64, i64)>>(&mut self, iter: I) {
+        for (start, end) in iter {
+            self.insert(start, end);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[(i64, i64)]) -> IntervalSet {
+        ranges.iter().copied().collect()
+    }
+
+    #[test]
+    fn inserts_merge_overlapping_and_touching_ranges() {
+        let ranges = set(&[(3, 5), (10, 14), (16, 20), (12, 18)]);
+        assert_eq!(ranges.ranges(), [(3, 5), (10, 20)]);
+        assert_eq!(ranges.len(), 14);
+
+        let ranges = set(&[(1, 2), (7, 8), (3, 4), (5, 6), (9, 9), (4, 2)]);
+        assert_eq!(ranges.ranges(), [(1, 9)]);
+        assert_eq!(set(&[(i64::MIN, -2), (0, i64::MAX)]).len(), u128::from(u64::MAX));
+        assert_eq!(set(&[(i64::MIN, i64::MAX)]).len(), 1 << 64);
+    }
+
+    #[test]
+    fn membership() {
+        let ranges = set(&[(3, 5), (10, 14), (16, 20), (12, 18)]);
+        let fresh: Vec<i64> = [1, 5, 8, 11, 17, 32].into_iter().filter(|&id| ranges.contains(id)).collect();
+        assert_eq!(fresh, [5, 11, 17]);
+        assert!(!IntervalSet::new().contains(0));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25), (28, 40)]);
+        assert_eq!(a.union(&b).ranges(), [(0, 40)]);
+        assert_eq!(a.intersection(&b).ranges(), [(5, 10), (20, 25), (28, 30)]);
+        assert_eq!(a.difference(&b).ranges(), [(0, 4), (26, 27)]);
+        assert_eq!(b.difference(&a).ranges(), [(11, 19), (31, 40)]);
+        assert_eq!(a.difference(&a), IntervalSet::new());
+        assert_eq!(set(&[(0, 10)]).difference(&set(&[(2, 3), (5, 5)])).ranges(), [(0, 1), (4, 4), (6, 10)]);
+        assert_eq!(set(&[(0, i64::MAX)]).difference(&set(&[(5, i64::MAX)])).ranges(), [(0, 4)]);
+        assert_eq!(set(&[(i64::MIN, i64::MAX)]).difference(&set(&[(i64::MIN, 0)])).ranges(), [(1, i64::MAX)]);
+    }
+}
@@ -1,5 +1,7 @@
+use std::fmt;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
+use std::str::FromStr;
 
 /// Read input for a given day as a String.
 /// It tries the following paths (first existing wins):
@@ -7,20 +9,21 @@ use std::path::{Path, PathBuf};
 /// - input/dayXX.txt
 /// - inputs/dayXX.txt
 pub fn read_input(day: u8) -> anyhow::Result<String> {
+    match input_path(day) {
+        Some(p) => Ok(fs::read_to_string(p)?),
+        None => anyhow::bail!("No input file found for day {day:02}"),
+    }
+}
+
+/// The file [`read_input`] reads for a given day, if any exists.
+pub fn input_path(day: u8) -> Option<PathBuf> {
     let candidates = [
         format!("inputs/day{day:02}/input.txt"),
         format!("input/day{day:02}.txt"),
         format!("inputs/day{day:02}.txt"),
     ];
 
-    for c in candidates {
-        let p = Path::new(&c);
-        if p.exists() {
-            return Ok(fs::read_to_string(p)?);
-        }
-    }
-
-    anyhow::bail!("No input file found for day {day:02}")
+    candidates.into_iter().map(PathBuf::from).find(|p| p.exists())
 }
 
 /// Convenience: split input into non-empty trimmed lines
@@ -37,4 +40,110 @@ pub fn repo_path(parts: &[&str]) -> PathBuf {
     p
 }
 
+/// A malformed puzzle input: where it went wrong and what the parser wanted there.
+///
+/// `line` and `column` are 1-based; `file` is filled in by the runner once it knows which file was read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub file: Option<PathBuf>,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub found: String,
+}
+
+impl ParseError {
+    /// Error for `token`, which must be a slice of `input`; its position is worked out from the pointers.
+    /// A token from anywhere else is reported at the end of the input.
+    pub fn at(input: &str, token: &str, expected: impl Into<String>) -> ParseError {
+        let start = input.as_ptr() as usize;
+        let offset = (token.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|&o| o + token.len() <= input.len())
+            .unwrap_or(input.len());
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        ParseError {
+            file: None,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            expected: expected.into(),
+            found: token.to_string(),
+        }
+    }
+
+    pub fn with_file(mut self, file: impl Into<PathBuf>) -> ParseError {
+        self.file = Some(file.into());
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file.display())?;
+        }
+        write!(f, "{}:{}: expected {}, found ", self.line, self.column, self.expected)?;
+        if self.found.is_empty() {
+            write!(f, "nothing")
+        } else {
+            write!(f, "{:?}", self.found)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parse `token` (a slice of `input`), reporting its position if it is not a valid `T`.
+pub fn parse_at<T: FromStr>(input: &str, token: &str, expected: &str) -> Result<T, ParseError> {
+    token.parse().map_err(|_| ParseError::at(input, token, expected))
+}
+
+pub mod answer;
+pub mod answers;
+pub mod bench;
 pub mod days;
+pub mod disjoint_set;
+pub mod geometry;
+pub mod gf2;
+pub mod graph;
+pub mod grid;
+pub mod ilp;
+pub mod intervals;
+pub mod linear;
+pub mod parse;
+pub mod runner;
+pub mod scaffold;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_error_locates_tokens() {
+        let input = "12x5: 1 0\n4x4: 0 ä?\n";
+        let bad = &input[19..20];
+        assert_eq!(bad, "?");
+        let err = ParseError::at(input, bad, "a count");
+        assert_eq!((err.line, err.column), (2, 9));
+        assert_eq!(err.to_string(), "2:9: expected a count, found \"?\"");
+
+        let err = err.with_file("inputs/day12/input.txt");
+        assert_eq!(err.to_string(), "inputs/day12/input.txt:2:9: expected a count, found \"?\"");
+    }
+
+    #[test]
+    fn parse_error_outside_input_points_at_the_end() {
+        let err = ParseError::at("1\n2\n", "", "a third line");
+        assert_eq!((err.line, err.column), (3, 1));
+        assert_eq!(err.to_string(), "3:1: expected a third line, found nothing");
+    }
+
+    #[test]
+    fn parse_at_reports_position() {
+        let input = "1,2,x";
+        assert_eq!(parse_at::<i64>(input, &input[2..3], "a number"), Ok(2));
+        let err = parse_at::<i64>(input, &input[4..5], "a number").unwrap_err();
+        assert_eq!((err.line, err.column, err.found.as_str()), (1, 5, "x"));
+    }
+}
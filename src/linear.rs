@@ -0,0 +1,125 @@
+//! Linear equations over the non-negative integers, such as how often to press each button so
+//! that every counter reaches its target.
+
+use crate::gf2;
+use std::collections::HashMap;
+
+/// A system `A x = b` where every coefficient of `A` and every `b` is a non-negative integer and
+/// only non-negative integer `x` count, built one equation at a time.
+#[derive(Clone, Debug, Default)]
+pub struct System {
+    variables: usize,
+    /// Each row holds one coefficient per variable.
+    rows: Vec<Vec<u64>>,
+    rhs: Vec<u64>,
+}
+
+/// The best solution found for each right-hand side already visited, `None` if it has none.
+type Memo = HashMap<Vec<u64>, Option<(u64, Vec<u64>)>>;
+
+impl System {
+    pub fn new(variables: usize) -> System {
+        System { variables, rows: vec![], rhs: vec![] }
+    }
+
+    pub fn variables(&self) -> usize {
+        self.variables
+    }
+
+    /// Add the equation "the sum of `terms` is `rhs`". A variable listed twice counts twice.
+    pub fn equation(&mut self, terms: impl IntoIterator<Item = usize>, rhs: u64) {
+        let mut row = vec![0; self.variables];
+        for term in terms {
+            assert!(term < self.variables, "variable {term} out of range for {} variables", self.variables);
+            row[term] += 1;
+        }
+        self.rows.push(row);
+        self.rhs.push(rhs);
+    }
+
+    /// A solution with the smallest sum, or `None` if there is no non-negative integer solution.
+    ///
+    /// Every solution splits as `x = 2y + s` with each `s` either 0 or 1. Then `A s` must have the
+    /// same parity as `b`, which is a system over GF(2) solved by elimination, and `y` solves the
+    /// same problem for `(b - A s) / 2`. The targets halve at every level, so the recursion is only
+    /// as deep as `b` has bits; its width is the number of parity solutions, which doubles with
+    /// each variable the GF(2) elimination leaves free; too many of those to enumerate is an error.
+    pub fn min_sum_solution(&self) -> Result<Option<Vec<u64>>, gf2::TooManyFree> {
+        Ok(self.halve(&self.rhs, &mut Memo::new())?.map(|(_, x)| x))
+    }
+
+    fn halve(&self, b: &[u64], memo: &mut Memo) -> Result<Option<(u64, Vec<u64>)>, gf2::TooManyFree> {
+        if b.iter().all(|&v| v == 0) {
+            return Ok(Some((0, vec![0; self.variables])));
+        }
+        if let Some(known) = memo.get(b) {
+            return Ok(known.clone());
+        }
+
+        let mut parity = gf2::System::new(self.variables);
+        for (row, &target) in self.rows.iter().zip(b) {
+            let odd = row.iter().enumerate().filter(|(_, &a)| a % 2 == 1).map(|(j, _)| j);
+            parity.equation(odd, target % 2 == 1);
+        }
+        let mut best: Option<(u64, Vec<u64>)> = None;
+        'parity: for s in parity.solutions()? {
+            // what is left of each target once every variable in `s` has been used once
+            let mut half = Vec::with_capacity(b.len());
+            for (row, &target) in self.rows.iter().zip(b) {
+                let used: u64 = row.iter().zip(&s).filter(|(_, &on)| on).map(|(&a, _)| a).sum();
+                let Some(left) = target.checked_sub(used) else {
+                    continue 'parity;
+                };
+                half.push(left / 2);
+            }
+            let Some((sum, y)) = self.halve(&half, memo)? else {
+                continue;
+            };
+            let sum = 2 * sum + s.iter().filter(|&&on| on).count() as u64;
+            if best.as_ref().is_none_or(|(fewest, _)| sum < *fewest) {
+                let x = y.iter().zip(&s).map(|(&y, &on)| 2 * y + u64::from(on)).collect();
+                best = Some((sum, x));
+            }
+        }
+        memo.insert(b.to_vec(), best.clone());
+        Ok(best)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn system(buttons: &[&[usize]], targets: &[u64]) -> System {
+        let mut system = System::new(buttons.len());
+        for (counter, &target) in targets.iter().enumerate() {
+            let wired = buttons.iter().enumerate().filter(|(_, b)| b.contains(&counter));
+            system.equation(wired.map(|(i, _)| i), target);
+        }
+        system
+    }
+
+    #[test]
+    fn finds_the_smallest_sum() {
+        // the first example machine: buttons (3) (1,3) (2) (2,3) (0,2) (0,1) and joltages {3,5,4,7}
+        let buttons: [&[usize]; 6] = [&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]];
+        let x = system(&buttons, &[3, 5, 4, 7]).min_sum_solution().unwrap().unwrap();
+        assert_eq!(x.iter().sum::<u64>(), 10);
+        for (counter, target) in [3, 5, 4, 7].into_iter().enumerate() {
+            let reached: u64 = (0..buttons.len()).filter(|&b| buttons[b].contains(&counter)).map(|b| x[b]).sum();
+            assert_eq!(reached, target);
+        }
+    }
+
+    #[test]
+    fn rejects_fractional_and_negative_solutions() {
+        // x0 + x1 = 1 and x0 + x1 = 2 contradict; 2 x0 = 3 has only a fractional solution
+        assert_eq!(system(&[&[0, 1], &[0, 1]], &[1, 2]).min_sum_solution(), Ok(None));
+        let mut halves = System::new(1);
+        halves.equation([0, 0], 3);
+        assert_eq!(halves.min_sum_solution(), Ok(None));
+        // x0 + x1 = 1 and x1 = 2 would need x0 = -1
+        assert_eq!(system(&[&[0], &[0, 1]], &[1, 2]).min_sum_solution(), Ok(None));
+        assert_eq!(System::new(2).min_sum_solution(), Ok(Some(vec![0, 0])));
+    }
+}
@@ -0,0 +1,201 @@
+//! Small parsing combinators shared by the days.
+//!
+//! Every function takes the whole puzzle `input` next to the piece being parsed, which must be a
+//! slice of it, so that a [`ParseError`] can point at the offending line and column. The pieces they
+//! hand back are slices of `input` as well and can be fed to the next combinator.
+
+use crate::{parse_at, ParseError};
+use std::str::FromStr;
+
+/// Blocks of consecutive non-blank lines, e.g. the two halves of day 5 or the shapes of day 12.
+/// Sections are trimmed of surrounding blank lines; any number of blank lines separates them.
+pub fn sections(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+    std::iter::from_fn(move || {
+        let is_blank = |text: &str, from: usize| {
+            let end = text[from..].find('\n').map_or(text.len(), |i| from + i + 1);
+            (text[from..end].trim().is_empty(), end)
+        };
+        while !rest.is_empty() {
+            match is_blank(rest, 0) {
+                (true, end) => rest = &rest[end..],
+                (false, _) => break,
+            }
+        }
+        if rest.is_empty() {
+            return None;
+        }
+        let mut end = 0;
+        while end < rest.len() {
+            match is_blank(rest, end) {
+                (true, _) => break,
+                (false, next) => end = next,
+            }
+        }
+        let section = rest[..end].trim_end();
+        rest = &rest[end..];
+        Some(section)
+    })
+}
+
+/// Every signed integer in `text`, ignoring whatever separates them. A `-` counts as a sign only
+/// when it does not follow a digit, so `3-5` is `[3, 5]` while `x=-5` is `[-5]`.
+pub fn ints<T: FromStr>(input: &str, text: &str, expected: &str) -> Result<Vec<T>, ParseError> {
+    numbers(input, text, true, expected)
+}
+
+/// Every run of digits in `text`; any `-` is treated as a separator.
+pub fn uints<T: FromStr>(input: &str, text: &str, expected: &str) -> Result<Vec<T>, ParseError> {
+    numbers(input, text, false, expected)
+}
+
+fn numbers<T: FromStr>(input: &str, text: &str, signed: bool, expected: &str) -> Result<Vec<T>, ParseError> {
+    let bytes = text.as_bytes();
+    let mut out = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let mut start = i;
+        if signed && start > 0 && bytes[start - 1] == b'-' && (start < 2 || !bytes[start - 2].is_ascii_digit()) {
+            start -= 1;
+        }
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        out.push(parse_at(input, &text[start..i], expected)?);
+    }
+    Ok(out)
+}
+
+/// Two values separated by `sep`, e.g. `12x5` with `'x'`. Both sides are trimmed.
+pub fn pair<T: FromStr>(input: &str, text: &str, sep: char, expected: &str) -> Result<(T, T), ParseError> {
+    let text = text.trim();
+    // skip the first character so that a leading minus sign is not taken for the separator
+    let split = text.char_indices().skip(1).find(|&(_, c)| c == sep);
+    let Some((at, _)) = split else {
+        return Err(ParseError::at(input, text, expected));
+    };
+    let (first, second) = (text[..at].trim(), text[at + sep.len_utf8()..].trim());
+    Ok((parse_at(input, first, expected)?, parse_at(input, second, expected)?))
+}
+
+/// An inclusive range written as `start-end`.
+pub fn range<T: FromStr>(input: &str, text: &str) -> Result<(T, T), ParseError> {
+    pair(input, text, '-', "a range like `3-5`")
+}
+
+/// Values separated by `sep`. Items are trimmed and empty items are skipped, so trailing
+/// separators and line breaks do no harm.
+pub fn list<T: FromStr>(input: &str, text: &str, sep: char, expected: &str) -> Result<Vec<T>, ParseError> {
+    text.split(sep)
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| parse_at(input, item, expected))
+        .collect()
+}
+
+/// Whitespace-separated values.
+pub fn words<T: FromStr>(input: &str, text: &str, expected: &str) -> Result<Vec<T>, ParseError> {
+    text.split_whitespace().map(|word| parse_at(input, word, expected)).collect()
+}
+
+/// Exactly `N` values separated by `sep`, e.g. the `x,y,z` of a junction box.
+pub fn array<T: FromStr, const N: usize>(
+    input: &str,
+    text: &str,
+    sep: char,
+    expected: &str,
+) -> Result<[T; N], ParseError> {
+    let values: Vec<T> = list(input, text, sep, expected)?;
+    values
+        .try_into()
+        .map_err(|_| ParseError::at(input, text.trim(), format!("{N} values separated by `{sep}`")))
+}
+
+/// A `key<sep>value` line such as `aaa: you hhh`. The key must not be empty; both halves are trimmed.
+pub fn key_value<'a>(input: &str, line: &'a str, sep: char) -> Result<(&'a str, &'a str), ParseError> {
+    let Some((key, value)) = line.split_once(sep) else {
+        return Err(ParseError::at(input, line.trim(), format!("`key{sep} value`")));
+    };
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(ParseError::at(input, key, "a key"));
+    }
+    Ok((key, value.trim()))
+}
+
+/// The contents of `text` between `open` and `close`, e.g. `(1,3)` gives `1,3`.
+pub fn bracketed<'a>(input: &str, text: &'a str, open: char, close: char) -> Result<&'a str, ParseError> {
+    let text = text.trim();
+    text.strip_prefix(open)
+        .and_then(|inner| inner.strip_suffix(close))
+        .ok_or_else(|| ParseError::at(input, text, format!("`{open}...{close}`")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_sections_on_blank_lines() {
+        let input = "\n0:\n###\n\n\n1:\n#.#\n  \n4x4: 0 1\n";
+        let found: Vec<&str> = sections(input).collect();
+        assert_eq!(found, ["0:\n###", "1:\n#.#", "4x4: 0 1"]);
+        assert_eq!(sections("").count(), 0);
+        assert_eq!(sections("3-5\r\n\r\n1\r\n").collect::<Vec<_>>(), ["3-5", "1"]);
+    }
+
+    #[test]
+    fn sections_keep_positions() {
+        let input = "1-2\n\n7\nx\n";
+        let ids = sections(input).nth(1).unwrap();
+        let err = words::<i64>(input, ids, "an ID").unwrap_err();
+        assert_eq!((err.line, err.column, err.found.as_str()), (4, 1, "x"));
+    }
+
+    #[test]
+    fn extracts_integers() {
+        let input = "p=3,-4 v=10-20 x--7";
+        assert_eq!(ints::<i64>(input, input, "a number"), Ok(vec![3, -4, 10, 20, -7]));
+        assert_eq!(uints::<u32>(input, input, "a number"), Ok(vec![3, 4, 10, 20, 7]));
+        let err = uints::<u8>(input, "1 300", "a byte").unwrap_err();
+        assert_eq!(err.found, "300");
+    }
+
+    #[test]
+    fn parses_pairs_and_ranges() {
+        let input = "11-22\n-5-3\n12x5\n7";
+        assert_eq!(range::<i64>(input, &input[..5]), Ok((11, 22)));
+        assert_eq!(range::<i64>(input, &input[6..10]), Ok((-5, 3)));
+        assert_eq!(pair::<u32>(input, &input[11..15], 'x', "a size"), Ok((12, 5)));
+        let err = range::<i64>(input, &input[16..]).unwrap_err();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (4, 1, "a range like `3-5`"));
+    }
+
+    #[test]
+    fn parses_lists() {
+        let input = "1, 2,,3,\n4 5  6\n1,2";
+        assert_eq!(list::<i64>(input, &input[..8], ',', "a number"), Ok(vec![1, 2, 3]));
+        assert_eq!(words::<i64>(input, &input[9..15], "a number"), Ok(vec![4, 5, 6]));
+        assert_eq!(array::<i64, 2>(input, &input[16..], ',', "a coordinate"), Ok([1, 2]));
+        let err = array::<i64, 3>(input, &input[16..], ',', "a coordinate").unwrap_err();
+        assert_eq!((err.line, err.column, err.found.as_str()), (3, 1, "1,2"));
+        assert_eq!(err.expected, "3 values separated by `,`");
+    }
+
+    #[test]
+    fn parses_keys_and_brackets() {
+        let input = "aaa: you hhh\n: x\n[.##.] (1,3)";
+        assert_eq!(key_value(input, &input[..12], ':'), Ok(("aaa", "you hhh")));
+        let err = key_value(input, &input[13..16], ':').unwrap_err();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (2, 1, "a key"));
+
+        assert_eq!(bracketed(input, &input[17..23], '[', ']'), Ok(".##."));
+        assert_eq!(bracketed(input, &input[24..], '(', ')'), Ok("1,3"));
+        let err = bracketed(input, &input[24..], '{', '}').unwrap_err();
+        assert_eq!((err.line, err.column), (3, 8));
+    }
+}
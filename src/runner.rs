@@ -0,0 +1,179 @@
+use crate::answer::Answer;
+use crate::days::Day;
+use crate::ParseError;
+use anyhow::{Context, Result};
+use std::any::Any;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Which half of a puzzle to run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+
+    pub fn from_number(n: u8) -> Result<Part> {
+        match n {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            _ => anyhow::bail!("part must be 1 or 2, got {n}"),
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Part {}", self.number())
+    }
+}
+
+/// Outcome of running one part: `None` if the day has no such part.
+pub struct PartRun {
+    pub part: Part,
+    pub answer: Option<Result<Answer>>,
+    pub elapsed: Duration,
+}
+
+/// Outcome of running a day on one input: how long parsing took, then each requested part.
+pub struct DayRun {
+    pub parse_elapsed: Duration,
+    pub parts: Vec<PartRun>,
+    /// The parsed input the parts ran on, kept for [`DayRun::report`].
+    parsed: Box<dyn Any>,
+}
+
+impl DayRun {
+    /// The day's notes on how the parts went (see [`Day::report`]), from the same parsed input and
+    /// what each part returned.
+    pub fn report(&self, day: &dyn Day) -> Result<Vec<String>> {
+        let outcomes: Vec<(u8, &Result<Answer>)> =
+            self.parts.iter().filter_map(|run| Some((run.part.number(), run.answer.as_ref()?))).collect();
+        day.report(self.parsed.as_ref(), &outcomes)
+    }
+}
+
+/// Parse once, then run the requested parts on the parsed input with the day's default parameters
+/// overridden by `params`. Parsing is timed on its own, so each part's time covers solving only.
+pub fn run_day(day: &dyn Day, input: &str, parts: &[Part], params: &toml::Table) -> Result<DayRun> {
+    let params = day.params(params)?;
+    let now = Instant::now();
+    let parsed = day.parse(input)?;
+    let parse_elapsed = now.elapsed();
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            let now = Instant::now();
+            let answer = match part {
+                Part::One => Some(day.part1(parsed.as_ref(), params.as_ref())),
+                Part::Two => day.part2(parsed.as_ref(), params.as_ref()),
+            };
+            PartRun { part, answer, elapsed: now.elapsed() }
+        })
+        .collect();
+    Ok(DayRun { parse_elapsed, parts, parsed })
+}
+
+/// Like [`run_day`] on the contents of `path`; parse errors are tagged with the file name.
+pub fn run_file(day: &dyn Day, path: &Path, parts: &[Part], params: &toml::Table) -> Result<DayRun> {
+    let input = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let mut run = run_day(day, &input, parts, params).map_err(|e| with_file(e, path))?;
+    for part in &mut run.parts {
+        part.answer = part.answer.take().map(|answer| answer.map_err(|e| with_file(e, path)));
+    }
+    Ok(run)
+}
+
+/// Write the ILP models the day builds for `path` (see [`Day::models`]) to `dir` as
+/// `dayXX-<name>.lp` and `dayXX-<name>.mps`, only those named in `only` unless it is empty.
+/// Returns how many models were written.
+pub fn export_models(day: &dyn Day, path: &Path, params: &toml::Table, only: &[String], dir: &Path) -> Result<usize> {
+    let input = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let params = day.params(params)?;
+    let parsed = day.parse(&input).map_err(|e| with_file(e, path))?;
+    let models = day.models(parsed.as_ref(), params.as_ref())?;
+    anyhow::ensure!(!models.is_empty(), "day {:02} builds no ILP models", day.day());
+    if let Some(missing) = only.iter().find(|name| !models.iter().any(|m| &m.name == *name)) {
+        let (first, last) = (&models[0].name, &models[models.len() - 1].name);
+        anyhow::bail!("day {:02} has no model {missing:?}; its models run from {first} to {last}", day.day());
+    }
+
+    std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    let mut written = 0;
+    for pending in models.into_iter().filter(|m| only.is_empty() || only.contains(&m.name)) {
+        let name = format!("day{:02}-{}", day.day(), pending.name);
+        let model = pending.build();
+        for (extension, text) in [("lp", model.to_lp(&name)), ("mps", model.to_mps(&name))] {
+            let file = dir.join(format!("{name}.{extension}"));
+            std::fs::write(&file, text).with_context(|| format!("writing {}", file.display()))?;
+        }
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// A `key=value` parameter override from the command line. The value is read as TOML (`10`,
+/// `"svr"`, `["fft", "dac"]`), falling back to a plain string so `start=you` works unquoted.
+pub fn parse_param(text: &str) -> Result<(String, toml::Value)> {
+    let Some((key, value)) = text.split_once('=') else {
+        anyhow::bail!("expected a parameter like `key=value`, got {text:?}");
+    };
+    let (key, value) = (key.trim(), value.trim());
+    anyhow::ensure!(!key.is_empty(), "parameter {text:?} has no name");
+    let value = match format!("value = {value}").parse::<toml::Table>() {
+        Ok(mut table) => table.remove("value").unwrap_or_else(|| toml::Value::String(value.to_string())),
+        Err(_) => toml::Value::String(value.to_string()),
+    };
+    Ok((key.to_string(), value))
+}
+
+/// Attach `path` to a [`ParseError`] so it reads `file:line:column: ...`.
+pub fn with_file(error: anyhow::Error, path: &Path) -> anyhow::Error {
+    match error.downcast::<ParseError>() {
+        Ok(e) => e.with_file(path).into(),
+        Err(e) => e,
+    }
+}
+
+/// Every `*.txt` file in `inputs/dayXX`, sorted by name (so `example*.txt` comes before `input.txt`).
+pub fn input_files(inputs: &Path, day: u8) -> Result<Vec<PathBuf>> {
+    let dir = inputs.join(format!("day{day:02}"));
+    if !dir.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+pub fn print_header(day: &dyn Day) {
+    println!("Day {:02}: {}", day.day(), day.title());
+}
+
+/// The parse timing line, e.g. `Parse:                       (310.50µs)`, aligned with the parts.
+pub fn format_parse(run: &DayRun) -> String {
+    format!("{:<28} ({:.2?})", "Parse:", run.parse_elapsed)
+}
+
+/// One result line, e.g. `Part 1: 1227775554           (1.20ms)`.
+pub fn format_part(run: &PartRun) -> String {
+    match &run.answer {
+        Some(Ok(answer)) => format!("{}: {answer:<20} ({:.2?})", run.part, run.elapsed),
+        Some(Err(e)) => format!("{}: error: {e:#}", run.part),
+        None => format!("{}: -", run.part),
+    }
+}
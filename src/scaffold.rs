@@ -0,0 +1,137 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const TEMPLATE: &str = r#"use crate::answer::Answer;
+use crate::days::{NoParams, Solution};
+use crate::lines;
+use anyhow::Result;
+
+pub struct Day{{DAY}};
+
+impl Solution for Day{{DAY}} {
+    /// The parsed puzzle input, built once and shared by both parts.
+    type Input = Vec<String>;
+    /// Swap for a `Params` struct if the examples use different constants than the real input.
+    type Params = NoParams;
+
+    const DAY: u8 = {{NUMBER}};
+    const TITLE: &'static str = "{{TITLE}}";
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        Ok(lines(input).map(str::to_string).collect())
+    }
+
+    fn part1(&self, _input: &Self::Input, _params: &Self::Params) -> Result<Answer> {
+        anyhow::bail!("day {{DAY}} part 1 is not solved yet")
+    }
+
+    fn part2(&self, _input: &Self::Input, _params: &Self::Params) -> Option<Result<Answer>> {
+        Some(Err(anyhow::anyhow!("day {{DAY}} part 2 is not solved yet")))
+    }
+}
+"#;
+
+/// Generate `src/days/dayXX.rs`, register it in `src/days/mod.rs` and create
+/// `inputs/dayXX/example.txt`, all relative to the repository `root`.
+///
+/// Refuses to touch a day whose module already exists or is already registered.
+/// Returns the files that were created or changed.
+pub fn new_day(root: &Path, day: u8, title: &str) -> Result<Vec<PathBuf>> {
+    anyhow::ensure!((1..=25).contains(&day), "day must be between 1 and 25, got {day}");
+    let module = root.join(format!("src/days/day{day:02}.rs"));
+    let mod_rs = root.join("src/days/mod.rs");
+    anyhow::ensure!(!module.exists(), "{} already exists", module.display());
+
+    let registry = fs::read_to_string(&mod_rs).with_context(|| format!("reading {}", mod_rs.display()))?;
+    let registry = register(&registry, day)?;
+
+    let source = TEMPLATE
+        .replace("{{DAY}}", &format!("{day:02}"))
+        .replace("{{NUMBER}}", &day.to_string())
+        .replace("{{TITLE}}", &title.replace('\\', "\\\\").replace('"', "\\\""));
+    fs::write(&module, source)?;
+    fs::write(&mod_rs, registry)?;
+    let mut touched = vec![module, mod_rs];
+
+    let example = root.join(format!("inputs/day{day:02}/example.txt"));
+    if !example.exists() {
+        fs::create_dir_all(example.parent().unwrap_or(root))?;
+        fs::write(&example, "")?;
+        touched.push(example);
+    }
+    Ok(touched)
+}
+
+/// Add `pub mod dayXX;` and `&dayXX::DayXX,` to the source of `days/mod.rs`, keeping both lists sorted.
+fn register(source: &str, day: u8) -> Result<String> {
+    let mod_line = format!("pub mod day{day:02};");
+    let entry_line = format!("    &day{day:02}::Day{day:02},");
+    anyhow::ensure!(!source.contains(&mod_line), "day {day:02} is already registered");
+
+    let lines: Vec<&str> = source.lines().collect();
+    let is_mod = |l: &str| l.starts_with("pub mod day");
+    let is_entry = |l: &str| l.trim_start().starts_with("&day") && l.trim_end().ends_with(',');
+    let mods = insert_sorted(&lines, is_mod, &mod_line).context("no `pub mod dayXX;` lines in days/mod.rs")?;
+    let lines: Vec<&str> = mods.iter().map(String::as_str).collect();
+    let entries = insert_sorted(&lines, is_entry, &entry_line).context("no registry entries in days/mod.rs")?;
+
+    let mut out = entries.join("\n");
+    if source.ends_with('\n') {
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Insert `new` into the contiguous block of lines matching `in_block`, in sorted position.
+fn insert_sorted(lines: &[&str], in_block: impl Fn(&str) -> bool, new: &str) -> Option<Vec<String>> {
+    let start = lines.iter().position(|l| in_block(l))?;
+    let len = lines[start..].iter().take_while(|l| in_block(l)).count();
+    let offset = lines[start..start + len].iter().take_while(|l| **l < new).count();
+    let mut out: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+    out.insert(start + offset, new.to_string());
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOD_RS: &str = "use anyhow::Result;\n\npub mod day01;\npub mod day03;\n\n\
+        pub static REGISTRY: &[&dyn Day] = &[\n    &day01::Day01,\n    &day03::Day03,\n];\n";
+
+    #[test]
+    fn registers_in_sorted_position() {
+        let out = register(MOD_RS, 2).unwrap();
+        assert!(out.contains("pub mod day01;\npub mod day02;\npub mod day03;\n"));
+        assert!(out.contains("    &day01::Day01,\n    &day02::Day02,\n    &day03::Day03,\n];\n"));
+
+        let out = register(MOD_RS, 12).unwrap();
+        assert!(out.contains("pub mod day03;\npub mod day12;\n\n"));
+        assert!(out.contains("    &day03::Day03,\n    &day12::Day12,\n];\n"));
+    }
+
+    #[test]
+    fn refuses_registered_days() {
+        assert!(register(MOD_RS, 3).is_err());
+    }
+
+    #[test]
+    fn creates_and_refuses_to_overwrite() {
+        let root = std::env::temp_dir().join(format!("aoc2025-scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src/days")).unwrap();
+        fs::write(root.join("src/days/mod.rs"), MOD_RS).unwrap();
+
+        let touched = new_day(&root, 2, "Gift \"Shop\"").unwrap();
+        assert_eq!(touched.len(), 3);
+        let source = fs::read_to_string(root.join("src/days/day02.rs")).unwrap();
+        assert!(source.contains("pub struct Day02;"));
+        assert!(source.contains("const DAY: u8 = 2;"));
+        assert!(source.contains(r#"const TITLE: &'static str = "Gift \"Shop\"";"#));
+        assert!(root.join("inputs/day02/example.txt").exists());
+
+        assert!(new_day(&root, 2, "again").is_err());
+        assert!(new_day(&root, 0, "zero").is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
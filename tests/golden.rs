@@ -0,0 +1,76 @@
+//! One test per input file per part: every `inputs/dayXX/*.txt` is run, with any `params` recorded
+//! for it, and compared with `answers.toml`.
+//!
+//! Inputs listed in `answers.toml` but not checked in are reported as ignored (`[missing]`), as are
+//! files without a recorded answer (`[unverified]`); `cargo test -- --include-ignored` runs them anyway.
+
+use aoc2025::answer::Answer;
+use aoc2025::answers::{Answers, ANSWERS_FILE};
+use aoc2025::days::{Day, REGISTRY};
+use aoc2025::repo_path;
+use aoc2025::runner::{self, Part};
+use libtest_mimic::{Arguments, Failed, Trial};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+fn main() {
+    let args = Arguments::from_args();
+    let answers = Answers::load(&repo_path(&[ANSWERS_FILE])).expect("invalid answers.toml");
+    let inputs = repo_path(&["inputs"]);
+
+    let mut trials = vec![];
+    for &day in REGISTRY {
+        let on_disk: Vec<PathBuf> = runner::input_files(&inputs, day.day()).expect("unreadable inputs directory");
+        let mut files: BTreeSet<String> = answers.files(day.day()).map(str::to_string).collect();
+        files.extend(on_disk.iter().filter_map(|p| Some(p.file_name()?.to_str()?.to_string())));
+
+        for file in files {
+            let path = inputs.join(format!("day{:02}", day.day())).join(&file);
+            for part in Part::BOTH {
+                let expected = answers.get(day.day(), &file, part).cloned();
+                // a recorded answer for a missing part still fails below
+                if part == Part::Two && !day.has_part2() && expected.is_none() {
+                    continue;
+                }
+                let name = format!("day{:02}::{file}::part{}", day.day(), part.number());
+                let trial = if !path.exists() {
+                    if expected.is_none() {
+                        continue;
+                    }
+                    let message = format!("{} is not checked in", path.display());
+                    Trial::test(name, move || Err(message.into()))
+                        .with_kind("missing")
+                        .with_ignored_flag(true)
+                } else {
+                    let (path, verified) = (path.clone(), expected.is_some());
+                    let params = answers.params(day.day(), &file);
+                    Trial::test(name, move || check(day, &path, part, &params, expected))
+                        .with_kind(if verified { "" } else { "unverified" })
+                        .with_ignored_flag(!verified)
+                };
+                trials.push(trial);
+            }
+        }
+    }
+
+    libtest_mimic::run(&args, trials).exit();
+}
+
+fn check(
+    day: &dyn Day,
+    path: &Path,
+    part: Part,
+    params: &toml::Table,
+    expected: Option<Answer>,
+) -> Result<(), Failed> {
+    let run = runner::run_file(day, path, &[part], params).map_err(|e| format!("{e:#}"))?.parts.remove(0);
+    match (run.answer, expected) {
+        (None, None) => Ok(()),
+        (None, Some(_)) => Err(format!("day {:02} has no {part}", day.day()).into()),
+        (Some(Err(e)), _) => Err(format!("{e:#}").into()),
+        (Some(Ok(answer)), Some(expected)) if answer != expected => {
+            Err(format!("got {answer}, expected {expected}").into())
+        }
+        (Some(Ok(_)), _) => Ok(()),
+    }
+}